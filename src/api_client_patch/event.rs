@@ -1,67 +1,249 @@
-use std::sync::mpsc::channel;
-use substrate_api_client::{api::Error as ApiClientError, api::Result as ApiResult, Error, ac_node_api::{Events, StaticEvent}, ac_primitives::Config, SubscribeEvents};
+use crate::{
+    primitives::{vc::ErrorDetail, AccountId},
+    Address, ApiClient,
+};
+use codec::{Decode, Encode};
+use sp_core::H256;
+use sp_runtime::{traits::Block as BlockT, DispatchError, MultiAddress};
+use std::{
+    sync::mpsc::{channel, Receiver, RecvTimeoutError},
+    thread,
+    time::{Duration, Instant},
+};
+use substrate_api_client::{
+    ac_node_api::{EventDetails, Events, Phase, StaticEvent},
+    ac_primitives::Config,
+    api::Error as ApiClientError,
+    api::Result as ApiResult,
+    rpc::WsRpcClient,
+    EventSubscriptionFor, GetChainInfo, SubscribeEvents,
+};
+use thiserror::Error;
 
-use crate::ApiClient;
+pub const SYSTEM_PALLET_NAME: &str = "System";
 
+/// Pallet events an `EventFilter` can match.
+pub trait FilterableEvent: StaticEvent {
+    /// The account the event is about.
+    fn account(&self) -> Option<&AccountId>;
+
+    /// Hash of the request extrinsic, for events the TEE worker emits in response to one.
+    fn req_ext_hash(&self) -> Option<H256> {
+        None
+    }
+}
+
+/// Which events to wait for, every criterion left empty matches all events.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EventFilter {
+    pub account: Option<AccountId>,
+    pub req_ext_hash: Option<H256>,
+}
+
+impl EventFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn account(mut self, account: AccountId) -> Self {
+        self.account = Some(account);
+        self
+    }
+
+    pub fn req_ext_hash(mut self, req_ext_hash: H256) -> Self {
+        self.req_ext_hash = Some(req_ext_hash);
+        self
+    }
+
+    pub fn matches<EventType: FilterableEvent>(&self, event: &EventType) -> bool {
+        let account_matches = match &self.account {
+            Some(account) => event.account() == Some(account),
+            None => true,
+        };
+        let req_ext_hash_matches = match self.req_ext_hash {
+            Some(req_ext_hash) => event.req_ext_hash() == Some(req_ext_hash),
+            None => true,
+        };
+
+        account_matches && req_ext_hash_matches
+    }
+}
+
+/// A request the TEE worker answered with a `*Failed` event.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("{event} for request {req_ext_hash:?}: {detail:?}")]
+pub struct RequestFailed {
+    pub event: &'static str,
+    pub account: Option<AccountId>,
+    pub detail: ErrorDetail,
+    pub req_ext_hash: H256,
+}
+
+/// Why waiting for events stopped early.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum WaitEventError {
+    #[error("{event} not found within {timeout:?}")]
+    Timeout {
+        event: &'static str,
+        timeout: Duration,
+    },
+    #[error("extrinsic {extrinsic_index} in block {block_hash:?} failed: {dispatch_error:?}")]
+    ExtrinsicFailed {
+        block_hash: H256,
+        extrinsic_index: u32,
+        dispatch_error: DispatchError,
+    },
+    #[error("event subscription closed")]
+    SubscriptionClosed,
+}
+
+impl From<WaitEventError> for ApiClientError {
+    fn from(error: WaitEventError) -> Self {
+        ApiClientError::Other(Box::new(error))
+    }
+}
+
+/// System.ExtrinsicFailed, only the leading `dispatch_error` field is decoded.
+#[derive(Decode, Debug)]
+pub struct ExtrinsicFailedEvent {
+    pub dispatch_error: DispatchError,
+}
+
+impl StaticEvent for ExtrinsicFailedEvent {
+    const PALLET: &'static str = SYSTEM_PALLET_NAME;
+    const EVENT: &'static str = "ExtrinsicFailed";
+}
+
+/// The events of each new block of an event subscription, received on a background thread so
+/// that waiting for them can time out. The subscription is closed after the receiver is dropped,
+/// once the next block arrives.
+pub struct EventReceiver {
+    receiver: Receiver<ApiResult<Events<H256>>>,
+}
+
+impl EventReceiver {
+    pub fn new(mut subscription: EventSubscriptionFor<WsRpcClient, H256>) -> Self {
+        let (sender, receiver) = channel();
+        thread::spawn(move || {
+            while let Some(events) = subscription.next_events_from_metadata() {
+                if sender.send(events).is_err() {
+                    break;
+                }
+            }
+            subscription.unsubscribe();
+        });
+
+        Self::from_receiver(receiver)
+    }
+
+    fn from_receiver(receiver: Receiver<ApiResult<Events<H256>>>) -> Self {
+        EventReceiver { receiver }
+    }
+
+    /// The events of the next block, or `None` when `deadline` passed first.
+    pub fn next_before(&self, deadline: Instant) -> ApiResult<Option<Events<H256>>> {
+        let timeout = deadline.saturating_duration_since(Instant::now());
+        match self.receiver.recv_timeout(timeout) {
+            Ok(events) => events.map(Some),
+            Err(RecvTimeoutError::Timeout) => Ok(None),
+            Err(RecvTimeoutError::Disconnected) => Err(WaitEventError::SubscriptionClosed.into()),
+        }
+    }
+
+    /// The events of the next block, `WaitEventError::Timeout` for `EventType` when `deadline`
+    /// passed first, `timeout` being the wait that led to it.
+    fn next_events<EventType: StaticEvent>(
+        &self,
+        deadline: Instant,
+        timeout: Duration,
+    ) -> ApiResult<Events<H256>> {
+        self.next_before(deadline)?.ok_or_else(|| {
+            WaitEventError::Timeout {
+                event: EventType::EVENT,
+                timeout,
+            }
+            .into()
+        })
+    }
+}
+
+/// Waiting gives up with `WaitEventError::Timeout` once `ApiClient::event_timeout` is exceeded,
+/// whether or not new blocks arrive.
+///
+/// When `filter.account` is set, a `System.ExtrinsicFailed` for an extrinsic signed by that
+/// account ends the wait with `WaitEventError::ExtrinsicFailed`. `wait_request_event` needs no
+/// such check, `send_extrinsic` already rejects a request extrinsic that failed.
 pub trait SubscribeEventPatch {
+    type Subscription;
+
     // For subscribe specific event
-    fn wait_event<EventType: StaticEvent>(&self) -> ApiResult<EventType>;
+    fn wait_event<EventType: FilterableEvent>(
+        &self,
+        subscription: &mut Self::Subscription,
+        filter: &EventFilter,
+    ) -> ApiResult<(H256, EventType)>;
 
     // For subscribe batch call events
-    fn wait_events<EventType: StaticEvent>(&self, target_num: usize) -> ApiResult<Vec<EventType>>;
+    fn wait_events<EventType: FilterableEvent>(
+        &self,
+        subscription: &mut Self::Subscription,
+        filter: &EventFilter,
+        target_num: usize,
+    ) -> ApiResult<Vec<(H256, EventType)>>;
 
-    // For wait error
-    fn wait_error<EventType: StaticEvent>(&self) -> ApiResult<EventType>;
+    /// Wait until the TEE worker answers the request `req_ext_hash` with either `Success` or `Failed`.
+    /// Returns the hash of the block the `Success` event was emitted in, a `Failed` event is
+    /// turned into an `ApiClientError::Other` wrapping a `RequestFailed`.
+    fn wait_request_event<Success, Failed>(
+        &self,
+        subscription: &mut Self::Subscription,
+        req_ext_hash: &H256,
+    ) -> ApiResult<(H256, Success)>
+    where
+        Success: FilterableEvent,
+        Failed: FilterableEvent + Into<RequestFailed>;
 }
 
-impl<T: Config> SubscribeEventPatch for ApiClient<T>
+impl<T> SubscribeEventPatch for ApiClient<T>
+where
+    T: Config<Hash = H256>,
 {
-    fn wait_event<EventType: StaticEvent>(&self) -> ApiResult<EventType> {
-        let (events_in, events_out) = channel();
-        self.api.subscribe_events()?;
+    type Subscription = EventReceiver;
 
-        let event: ApiResult<EventType> = self.api.wait_for_event(&events_out);
-        event
+    fn wait_event<EventType: FilterableEvent>(
+        &self,
+        subscription: &mut Self::Subscription,
+        filter: &EventFilter,
+    ) -> ApiResult<(H256, EventType)> {
+        let mut events = self.wait_events(subscription, filter, 1)?;
+        events
+            .pop()
+            .ok_or_else(|| WaitEventError::SubscriptionClosed.into())
     }
 
-    fn wait_events<EventType: StaticEvent>(&self, target_num: usize) -> ApiResult<Vec<EventType>> {
-        let (events_in, events_out) = channel();
-        self.api.subscribe_events()?;
+    fn wait_events<EventType: FilterableEvent>(
+        &self,
+        subscription: &mut Self::Subscription,
+        filter: &EventFilter,
+        target_num: usize,
+    ) -> ApiResult<Vec<(H256, EventType)>> {
+        let deadline = Instant::now() + self.event_timeout;
 
         let mut collected_events = vec![];
-        loop {
-            if collected_events.len() == target_num {
-                break;
-            }
+        while collected_events.len() < target_num {
+            let events = subscription.next_events::<EventType>(deadline, self.event_timeout)?;
+            let block_hash = events.block_hash();
 
-            let events_str = events_out.recv()?;
-            let event_bytes = Vec::from_hex(events_str)?;
-            let events = Events::new(self.api.metadata().clone(), Default::default(), event_bytes);
-
-            for maybe_event_details in events.iter() {
-                let event_details = maybe_event_details?;
-                let event_metadata = event_details.event_metadata();
-                println!(
-                    "Found extrinsic: {:?}, {:?}",
-                    event_metadata.pallet(),
-                    event_metadata.event()
-                );
-
-                let pallet_name = event_metadata.pallet();
-                let pallet_event = event_metadata.event();
-
-                if pallet_name == "System" && pallet_event == "ExtrinsicFailed" {
-                    return Err(ApiClientError::Other(
-                        format!("System ExtrinsicFailed: {:?}", event_metadata).into(),
-                    ));
+            for event_details in events.iter() {
+                let event_details = event_details?;
+                if let Some(account) = &filter.account {
+                    self.check_extrinsic_failed(block_hash, &event_details, account)?;
                 }
 
-                if pallet_name == EventType::PALLET && pallet_event == EventType::EVENT {
-                    println!("meta: {:?}", event_metadata);
-                    let event = event_details
-                        .as_event::<EventType>()?
-                        .ok_or_else(|| Error::Other("Could not find the specific event".into()))?;
-                    collected_events.push(event);
+                if let Some(event) = event_details.as_event::<EventType>()? {
+                    if filter.matches(&event) {
+                        collected_events.push((block_hash, event));
+                    }
                 }
             }
         }
@@ -69,11 +251,288 @@ impl<T: Config> SubscribeEventPatch for ApiClient<T>
         Ok(collected_events)
     }
 
-    fn wait_error<EventType: StaticEvent>(&self) -> ApiResult<EventType> {
-        let (events_in, events_out) = channel();
-        self.api.subscribe_events(events_in)?;
+    fn wait_request_event<Success, Failed>(
+        &self,
+        subscription: &mut Self::Subscription,
+        req_ext_hash: &H256,
+    ) -> ApiResult<(H256, Success)>
+    where
+        Success: FilterableEvent,
+        Failed: FilterableEvent + Into<RequestFailed>,
+    {
+        let deadline = Instant::now() + self.event_timeout;
+        let filter = EventFilter::new().req_ext_hash(*req_ext_hash);
+
+        loop {
+            let events = subscription.next_events::<Success>(deadline, self.event_timeout)?;
+            let block_hash = events.block_hash();
+
+            for event_details in events.iter() {
+                let event_details = event_details?;
+                if let Some(event) = event_details.as_event::<Success>()? {
+                    if filter.matches(&event) {
+                        return Ok((block_hash, event));
+                    }
+                }
+
+                if let Some(event) = event_details.as_event::<Failed>()? {
+                    if filter.matches(&event) {
+                        let failed: RequestFailed = event.into();
+                        return Err(ApiClientError::Other(Box::new(failed)));
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<T> ApiClient<T>
+where
+    T: Config<Hash = H256>,
+{
+    /// Subscribe to the events of new blocks, for waiting with `SubscribeEventPatch`.
+    pub fn subscribe_event_receiver(&self) -> ApiResult<EventReceiver> {
+        Ok(EventReceiver::new(self.api.subscribe_events()?))
+    }
+
+    /// A `System.ExtrinsicFailed` of an extrinsic signed by `account` as an error.
+    fn check_extrinsic_failed(
+        &self,
+        block_hash: H256,
+        event_details: &EventDetails,
+        account: &AccountId,
+    ) -> ApiResult<()> {
+        let event = event_details.as_event::<ExtrinsicFailedEvent>()?;
+        check_failed(
+            block_hash,
+            event_details.phase(),
+            event,
+            account,
+            |extrinsic_index| self.extrinsic_signer(block_hash, extrinsic_index),
+        )
+    }
+
+    /// The account that signed extrinsic `extrinsic_index` of block `block_hash`.
+    pub fn extrinsic_signer(
+        &self,
+        block_hash: H256,
+        extrinsic_index: u32,
+    ) -> ApiResult<Option<AccountId>> {
+        let block = self.api.get_block(Some(block_hash))?.ok_or_else(|| {
+            ApiClientError::Other(format!("[+] block {:?} not found", block_hash).into())
+        })?;
+        match block.extrinsics().get(extrinsic_index as usize) {
+            Some(extrinsic) => signer_of(&extrinsic.encode()),
+            None => Ok(None),
+        }
+    }
+}
+
+/// `event`, a `System.ExtrinsicFailed` emitted in `phase`, as an error when the extrinsic was
+/// signed by `account`. The signer is only fetched for failed extrinsics.
+fn check_failed<F>(
+    block_hash: H256,
+    phase: Phase,
+    event: Option<ExtrinsicFailedEvent>,
+    account: &AccountId,
+    signer: F,
+) -> ApiResult<()>
+where
+    F: FnOnce(u32) -> ApiResult<Option<AccountId>>,
+{
+    let event = match event {
+        Some(event) => event,
+        None => return Ok(()),
+    };
+    let extrinsic_index = match phase {
+        Phase::ApplyExtrinsic(extrinsic_index) => extrinsic_index,
+        _ => return Ok(()),
+    };
+
+    if signer(extrinsic_index)?.as_ref() == Some(account) {
+        return Err(WaitEventError::ExtrinsicFailed {
+            block_hash,
+            extrinsic_index,
+            dispatch_error: event.dispatch_error,
+        }
+        .into());
+    }
+
+    Ok(())
+}
+
+/// The signer of an extrinsic, SCALE encoded with its length prefix.
+fn signer_of(extrinsic: &[u8]) -> ApiResult<Option<AccountId>> {
+    // Signed extrinsics start with a version byte with the high bit set, then the signer address.
+    let encoded: Vec<u8> = Decode::decode(&mut &extrinsic[..])?;
+    match encoded.split_first() {
+        Some((version, mut signature)) if version & 0b1000_0000 != 0 => {
+            match Address::decode(&mut signature)? {
+                MultiAddress::Id(account) => Ok(Some(account)),
+                _ => Ok(None),
+            }
+        }
+        _ => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::mock_metadata::mock_metadata;
+
+    #[derive(Decode, Debug)]
+    struct MockEvent {
+        account: AccountId,
+        req_ext_hash: H256,
+    }
+
+    impl StaticEvent for MockEvent {
+        const PALLET: &'static str = "Mock";
+        const EVENT: &'static str = "Mocked";
+    }
+
+    impl FilterableEvent for MockEvent {
+        fn account(&self) -> Option<&AccountId> {
+            Some(&self.account)
+        }
+
+        fn req_ext_hash(&self) -> Option<H256> {
+            Some(self.req_ext_hash)
+        }
+    }
+
+    #[test]
+    fn event_filter_matches_works() {
+        let alice = AccountId::new([1u8; 32]);
+        let bob = AccountId::new([2u8; 32]);
+        let event = MockEvent {
+            account: alice.clone(),
+            req_ext_hash: H256::repeat_byte(1),
+        };
+
+        assert!(EventFilter::new().matches(&event));
+        assert!(EventFilter::new().account(alice.clone()).matches(&event));
+        assert!(!EventFilter::new().account(bob).matches(&event));
+        assert!(EventFilter::new()
+            .account(alice.clone())
+            .req_ext_hash(H256::repeat_byte(1))
+            .matches(&event));
+        assert!(!EventFilter::new()
+            .account(alice)
+            .req_ext_hash(H256::repeat_byte(2))
+            .matches(&event));
+    }
+
+    fn wait_error<T>(result: ApiResult<T>) -> WaitEventError {
+        match result {
+            Err(ApiClientError::Other(error)) => error
+                .downcast_ref::<WaitEventError>()
+                .cloned()
+                .expect("a WaitEventError"),
+            Err(error) => panic!("expected a WaitEventError, got {:?}", error),
+            Ok(_) => panic!("expected a WaitEventError"),
+        }
+    }
+
+    #[test]
+    fn next_events_times_out_while_no_block_arrives() {
+        let (sender, receiver) = channel();
+        let subscription = EventReceiver::from_receiver(receiver);
+
+        let timeout = Duration::from_millis(50);
+        let start = Instant::now();
+        let error = wait_error(subscription.next_events::<MockEvent>(start + timeout, timeout));
+        assert!(start.elapsed() >= timeout);
+        assert_eq!(
+            error,
+            WaitEventError::Timeout {
+                event: "Mocked",
+                timeout,
+            }
+        );
+
+        // a deadline already passed still returns a block that arrived
+        let block_hash = H256::repeat_byte(1);
+        sender
+            .send(Ok(Events::new(mock_metadata(), block_hash, vec![0])))
+            .unwrap();
+        let events = subscription
+            .next_events::<MockEvent>(start, timeout)
+            .unwrap();
+        assert_eq!(events.block_hash(), block_hash);
+    }
+
+    #[test]
+    fn next_events_reports_a_closed_subscription() {
+        let (sender, receiver) = channel();
+        let subscription = EventReceiver::from_receiver(receiver);
+        drop(sender);
+
+        let deadline = Instant::now() + Duration::from_secs(10);
+        assert_eq!(
+            wait_error(subscription.next_before(deadline)),
+            WaitEventError::SubscriptionClosed
+        );
+    }
+
+    #[test]
+    fn failed_extrinsics_of_the_account_end_the_wait() {
+        let alice = AccountId::new([1u8; 32]);
+        let bob = AccountId::new([2u8; 32]);
+        let block_hash = H256::repeat_byte(1);
+        let failed = || {
+            Some(ExtrinsicFailedEvent {
+                dispatch_error: DispatchError::BadOrigin,
+            })
+        };
+
+        assert_eq!(
+            wait_error(check_failed(
+                block_hash,
+                Phase::ApplyExtrinsic(3),
+                failed(),
+                &alice,
+                |_| Ok(Some(alice.clone())),
+            )),
+            WaitEventError::ExtrinsicFailed {
+                block_hash,
+                extrinsic_index: 3,
+                dispatch_error: DispatchError::BadOrigin,
+            }
+        );
+        assert!(check_failed(
+            block_hash,
+            Phase::ApplyExtrinsic(3),
+            failed(),
+            &alice,
+            |_| Ok(Some(bob.clone())),
+        )
+        .is_ok());
+        assert!(
+            check_failed(block_hash, Phase::Finalization, failed(), &alice, |_| {
+                panic!("no extrinsic to fetch")
+            })
+            .is_ok()
+        );
+        assert!(
+            check_failed(block_hash, Phase::ApplyExtrinsic(3), None, &alice, |_| {
+                panic!("no extrinsic to fetch")
+            })
+            .is_ok()
+        );
+    }
+
+    #[test]
+    fn signer_of_works() {
+        let alice = AccountId::new([1u8; 32]);
+
+        let mut signed = vec![0b1000_0100];
+        signed.extend(Address::Id(alice.clone()).encode());
+        signed.extend([0u8; 8]);
+        assert_eq!(signer_of(&signed.encode()).unwrap(), Some(alice));
 
-        let vc_disabled_event: ApiResult<EventType> = self.api.wait_for_event(&events_out);
-        vc_disabled_event
+        let unsigned = vec![0b0000_0100, 0, 0];
+        assert_eq!(signer_of(&unsigned.encode()).unwrap(), None);
     }
 }
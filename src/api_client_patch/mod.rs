@@ -1,4 +1,4 @@
 // pub mod batch_all_x;
-// pub mod event;
 pub mod batch_all;
+pub mod event;
 pub mod parachain;
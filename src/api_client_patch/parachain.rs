@@ -23,6 +23,7 @@ pub trait ParachainPatch {
     // Teerex pallet
     fn enclave_count(&self) -> ApiResult<Option<u64>>;
     fn enclave(&self, enclave_count: u64) -> ApiResult<Option<Enclave<AccountId, String>>>;
+    fn enclaves(&self) -> ApiResult<Vec<Enclave<AccountId, String>>>;
     fn get_shard(&self) -> ApiResult<MrEnclave>;
     fn get_tee_shielding_pubkey(&self) -> ApiResult<RsaPublicKey>;
     fn get_vc_pubkey(&self) -> ApiResult<Ed25519Public>;
@@ -55,6 +56,20 @@ impl<T: Config> ParachainPatch for ApiClient<T> {
         )
     }
 
+    /// All registered enclaves, in registration order.
+    fn enclaves(&self) -> ApiResult<Vec<Enclave<AccountId, String>>> {
+        let enclave_count = self.enclave_count()?.unwrap_or_default();
+
+        let mut enclaves = vec![];
+        for index in 1..=enclave_count {
+            if let Some(enclave) = self.enclave(index)? {
+                enclaves.push(enclave);
+            }
+        }
+
+        Ok(enclaves)
+    }
+
     fn get_tee_shielding_pubkey(&self) -> ApiResult<RsaPublicKey> {
         let enclave_count: Option<u64> = self.enclave_count()?;
         let enclave_count = enclave_count.ok_or_else(|| {
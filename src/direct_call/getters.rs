@@ -0,0 +1,144 @@
+use crate::{
+    direct_call::{
+        primitives::{Getter, TrustedGetter, UserShieldingKeyType},
+        top::DirectCall,
+        types::KeyPair,
+    },
+    primitives::{
+        crypto::{DirectRequestStatus, RpcReturnValue},
+        identity::{Identity, IdentityContext},
+        AccountId, Balance, ChallengeCode,
+    },
+    sidechain::rpc::SidechainError,
+    utils::hex::FromHexPrefixed,
+    ApiClient,
+};
+use codec::Decode;
+use substrate_api_client::{ac_primitives::Config, api::Result as ApiResult};
+
+/// Trusted getters, signed by `signer` and answered with the decoded sidechain state of its account.
+pub trait TrustedGetterApi {
+    fn free_balance(&self, signer: &KeyPair) -> ApiResult<Balance>;
+    fn reserved_balance(&self, signer: &KeyPair) -> ApiResult<Balance>;
+    fn user_shielding_key(&self, signer: &KeyPair) -> ApiResult<Option<UserShieldingKeyType>>;
+    fn id_graph(&self, signer: &KeyPair) -> ApiResult<Vec<(Identity, IdentityContext)>>;
+    fn challenge_code(
+        &self,
+        signer: &KeyPair,
+        identity: &Identity,
+    ) -> ApiResult<Option<ChallengeCode>>;
+    fn id_graph_stats(&self, signer: &KeyPair) -> ApiResult<Vec<(AccountId, u32)>>;
+}
+
+impl<T: Config> TrustedGetterApi for ApiClient<T> {
+    fn free_balance(&self, signer: &KeyPair) -> ApiResult<Balance> {
+        let getter = TrustedGetter::free_balance(signer.account_id());
+        Ok(self
+            .execute_trusted_getter(&getter, signer)?
+            .unwrap_or_default())
+    }
+
+    fn reserved_balance(&self, signer: &KeyPair) -> ApiResult<Balance> {
+        let getter = TrustedGetter::reserved_balance(signer.account_id());
+        Ok(self
+            .execute_trusted_getter(&getter, signer)?
+            .unwrap_or_default())
+    }
+
+    fn user_shielding_key(&self, signer: &KeyPair) -> ApiResult<Option<UserShieldingKeyType>> {
+        let getter = TrustedGetter::user_shielding_key(signer.account_id());
+        self.execute_trusted_getter(&getter, signer)
+    }
+
+    fn id_graph(&self, signer: &KeyPair) -> ApiResult<Vec<(Identity, IdentityContext)>> {
+        let getter = TrustedGetter::id_graph(signer.account_id());
+        Ok(self
+            .execute_trusted_getter(&getter, signer)?
+            .unwrap_or_default())
+    }
+
+    fn challenge_code(
+        &self,
+        signer: &KeyPair,
+        identity: &Identity,
+    ) -> ApiResult<Option<ChallengeCode>> {
+        let getter = TrustedGetter::challenge_code(signer.account_id(), identity.clone());
+        self.execute_trusted_getter(&getter, signer)
+    }
+
+    fn id_graph_stats(&self, signer: &KeyPair) -> ApiResult<Vec<(AccountId, u32)>> {
+        let getter = TrustedGetter::id_graph_stats(signer.account_id());
+        Ok(self
+            .execute_trusted_getter(&getter, signer)?
+            .unwrap_or_default())
+    }
+}
+
+impl<T: Config> ApiClient<T> {
+    /// Sign `getter` with `signer` and decode its result, `None` when the state is not set.
+    pub fn execute_trusted_getter<R: Decode>(
+        &self,
+        getter: &TrustedGetter,
+        signer: &KeyPair,
+    ) -> ApiResult<Option<R>> {
+        let getter: Getter = getter.sign(signer).into();
+        self.execute_getter(&getter)
+    }
+
+    /// Send `getter` and decode its result, `None` when the state is not set.
+    pub fn execute_getter<R: Decode>(&self, getter: &Getter) -> ApiResult<Option<R>> {
+        let response = self.getter_request(getter)?;
+        decode_getter_result(&response.result)
+    }
+}
+
+/// A getter answers with a hex `RpcReturnValue`, its value is the SCALE encoded `Option<Vec<u8>>`
+/// of the encoded state.
+pub fn decode_getter_result<R: Decode>(rpc_return_value_hex: &str) -> ApiResult<Option<R>> {
+    let rpc_return_value = RpcReturnValue::from_hex(rpc_return_value_hex)
+        .map_err(|e| SidechainError::Parse(format!("{:?}", e)))?;
+
+    if rpc_return_value.status != DirectRequestStatus::Ok {
+        let reason = String::decode(&mut rpc_return_value.value.as_slice())
+            .unwrap_or_else(|_| hex::encode(&rpc_return_value.value));
+        return Err(SidechainError::Rejected(reason).into());
+    }
+
+    let encoded = Option::<Vec<u8>>::decode(&mut rpc_return_value.value.as_slice())?;
+    match encoded {
+        Some(encoded) => Ok(Some(R::decode(&mut encoded.as_slice())?)),
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::hex::ToHexPrefixed;
+    use codec::Encode;
+
+    #[test]
+    fn decode_getter_result_works() {
+        let stats: Vec<(AccountId, u32)> = vec![(AccountId::new([1u8; 32]), 3)];
+        let value = Some(stats.encode()).encode();
+        let rpc_return_value = RpcReturnValue::new(value, false, DirectRequestStatus::Ok);
+
+        let decoded: Option<Vec<(AccountId, u32)>> =
+            decode_getter_result(&rpc_return_value.to_hex()).unwrap();
+        assert_eq!(decoded, Some(stats));
+
+        let rpc_return_value =
+            RpcReturnValue::new(None::<Vec<u8>>.encode(), false, DirectRequestStatus::Ok);
+        let decoded: Option<ChallengeCode> =
+            decode_getter_result(&rpc_return_value.to_hex()).unwrap();
+        assert_eq!(decoded, None);
+    }
+
+    #[test]
+    fn decode_getter_result_maps_error_status() {
+        let rpc_return_value = RpcReturnValue::from_error_message("getter failed");
+
+        let result: ApiResult<Option<u128>> = decode_getter_result(&rpc_return_value.to_hex());
+        assert!(result.is_err());
+    }
+}
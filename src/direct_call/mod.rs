@@ -1,4 +1,7 @@
+pub mod getters;
+pub mod nonce;
 pub mod primitives;
 pub mod top;
 pub mod trusted_call_signed;
 pub mod types;
+pub mod watcher;
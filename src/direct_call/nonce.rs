@@ -0,0 +1,113 @@
+use crate::{
+    api_client_patch::parachain::ParachainPatch,
+    direct_call::{
+        primitives::{Getter, Index, PublicGetter},
+        top::{DirectCall, TrustedOperation},
+        trusted_call_signed::TrustedCall,
+        types::{AccountId, KeyPair},
+    },
+    primitives::crypto::TrustedOperationStatus,
+    ApiClient,
+};
+use sp_core::H256;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use substrate_api_client::{ac_primitives::Config, api::Result as ApiResult};
+
+/// Trusted-call nonces handed out per account, so concurrent submissions don't reuse a nonce.
+#[derive(Debug, Default)]
+pub struct NonceCache {
+    nonces: Mutex<HashMap<AccountId, Index>>,
+}
+
+impl NonceCache {
+    /// Reserve the next nonce of `account`, starting from `fetch` when it is not cached yet.
+    pub fn next_nonce<F>(&self, account: &AccountId, fetch: F) -> ApiResult<Index>
+    where
+        F: FnOnce() -> ApiResult<Index>,
+    {
+        let mut nonces = self.nonces.lock().unwrap();
+        let nonce = match nonces.get(account) {
+            Some(nonce) => *nonce,
+            None => fetch()?,
+        };
+        nonces.insert(account.clone(), nonce + 1);
+
+        Ok(nonce)
+    }
+
+    /// Forget the cached nonce of `account`, the next one is fetched from the sidechain again.
+    pub fn resync(&self, account: &AccountId) {
+        self.nonces.lock().unwrap().remove(account);
+    }
+}
+
+pub trait NonceManager {
+    /// The nonce the sidechain expects next from `account`.
+    fn sidechain_nonce(&self, account: &AccountId) -> ApiResult<Index>;
+
+    /// Reserve the next nonce for a trusted call signed by `signer`.
+    fn next_nonce(&self, signer: &KeyPair) -> ApiResult<Index>;
+
+    /// Sign `call` with the next nonce of `signer`, submit it and wait for its final status.
+    /// The cached nonce is resynced when the operation ends up `Invalid` or `Usurped`, or fails.
+    fn submit_trusted_call(
+        &self,
+        call: &TrustedCall,
+        signer: &KeyPair,
+    ) -> ApiResult<TrustedOperationStatus>;
+}
+
+impl<T: Config> NonceManager for ApiClient<T> {
+    fn sidechain_nonce(&self, account: &AccountId) -> ApiResult<Index> {
+        let getter: Getter = PublicGetter::nonce(account.clone()).into();
+        Ok(self.execute_getter(&getter)?.unwrap_or_default())
+    }
+
+    fn next_nonce(&self, signer: &KeyPair) -> ApiResult<Index> {
+        let account = signer.account_id();
+        self.nonces
+            .next_nonce(&account, || self.sidechain_nonce(&account))
+    }
+
+    fn submit_trusted_call(
+        &self,
+        call: &TrustedCall,
+        signer: &KeyPair,
+    ) -> ApiResult<TrustedOperationStatus> {
+        let account = signer.account_id();
+        let shard = self.get_shard()?;
+        let nonce = self.next_nonce(signer)?;
+
+        let top: TrustedOperation = call.sign(signer, nonce, &shard, &H256::from(shard)).into();
+        let status = self
+            .watch_di_request(&top)
+            .and_then(|watcher| watcher.wait_until_final());
+
+        match &status {
+            Ok(TrustedOperationStatus::Invalid) | Ok(TrustedOperationStatus::Usurped) | Err(_) => {
+                self.nonces.resync(&account)
+            }
+            _ => {}
+        }
+
+        status
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nonce_cache_works() {
+        let cache = NonceCache::default();
+        let alice = AccountId::new([1u8; 32]);
+
+        assert_eq!(cache.next_nonce(&alice, || Ok(5)).unwrap(), 5);
+        assert_eq!(cache.next_nonce(&alice, || Ok(0)).unwrap(), 6);
+
+        cache.resync(&alice);
+        assert_eq!(cache.next_nonce(&alice, || Ok(2)).unwrap(), 2);
+    }
+}
@@ -1,11 +1,13 @@
 use crate::api_client_patch::parachain::ParachainPatch;
 use crate::direct_call::primitives::Request;
 use crate::direct_call::trusted_call_signed::TrustedCallSigned;
+use crate::direct_call::watcher::TrustedOperationWatcher;
 use crate::sidechain::rpc::SidechainRpcClientTrait;
-use crate::sidechain::SidechainResp;
+use crate::sidechain::{json_request, SidechainResp};
 use crate::utils::crypto::encrypt_with_tee_shielding_pubkey;
 use crate::utils::hex::ToHexPrefixed;
 use crate::ApiClient;
+use serde_json::Value;
 use sp_core::{Decode, Encode};
 use substrate_api_client::ac_primitives::Config;
 use substrate_api_client::api::Result as ApiResult;
@@ -69,6 +71,12 @@ pub trait DirectCall {
     fn send_request_di(&self, operation_call: &TrustedOperation) -> ApiResult<SidechainResp>;
     fn getter_request(&self, top: &Getter) -> ApiResult<SidechainResp>;
     fn di_request(&self, operation_call: &TrustedOperation) -> ApiResult<SidechainResp>;
+
+    /// Submit `operation_call` and follow its `TrustedOperationStatus` updates.
+    fn watch_di_request(
+        &self,
+        operation_call: &TrustedOperation,
+    ) -> ApiResult<TrustedOperationWatcher>;
 }
 
 impl<T: Config> DirectCall for ApiClient<T> {
@@ -80,42 +88,54 @@ impl<T: Config> DirectCall for ApiClient<T> {
     }
 
     fn di_request(&self, operation_call: &TrustedOperation) -> ApiResult<SidechainResp> {
-        let shard = self.get_shard().unwrap();
-        let tee_shielding_key = self.get_tee_shielding_pubkey().unwrap();
-        let operation_call_encrypted =
-            encrypt_with_tee_shielding_pubkey(&tee_shielding_key, &operation_call.encode());
-
-        // compose jsonrpc call
-        let request = Request {
-            shard: sp_core::H256(shard),
-            cyphertext: operation_call_encrypted,
-        };
-
-        // author_submitVCRequest
-        use crate::sidechain::json_req;
-        let jsonreq = json_req("author_submitAndWatchExtrinsic", vec![request.to_hex()], 1);
+        let jsonreq = self.submit_and_watch_jsonreq(operation_call)?;
 
         use crate::sidechain::json_resp;
-        let res = self.sidechain.request(jsonreq).unwrap();
-        let x = json_resp(res).unwrap();
-        println!("x: {:?}", x);
+        let res = self.sidechain.request(jsonreq)?;
+        json_resp(res)
+    }
+
+    fn watch_di_request(
+        &self,
+        operation_call: &TrustedOperation,
+    ) -> ApiResult<TrustedOperationWatcher> {
+        let jsonreq = self.submit_and_watch_jsonreq(operation_call)?;
+        let subscription = self.sidechain.subscribe(jsonreq)?;
 
-        Ok(x)
+        Ok(TrustedOperationWatcher::new(subscription))
     }
 
     fn getter_request(&self, getter: &Getter) -> ApiResult<SidechainResp> {
-        let shard = self.get_shard().unwrap();
+        let shard = self.get_shard()?;
         let request = Request {
             shard: sp_core::H256(shard),
             cyphertext: getter.encode(),
         };
-        use crate::sidechain::json_req;
-        let jsonreq = json_req("state_executeGetter", vec![request.to_hex()], 1);
+        let jsonreq = json_request("state_executeGetter", vec![request.to_hex()]);
         use crate::sidechain::json_resp;
-        let res = self.sidechain.request(jsonreq).unwrap();
-        let x = json_resp(res).unwrap();
-        println!("x: {:?}", x);
+        let res = self.sidechain.request(jsonreq)?;
+        json_resp(res)
+    }
+}
+
+impl<T: Config> ApiClient<T> {
+    /// `author_submitAndWatchExtrinsic` with `operation_call` encrypted for the TEE.
+    fn submit_and_watch_jsonreq(&self, operation_call: &TrustedOperation) -> ApiResult<Value> {
+        let shard = self.get_shard()?;
+        let tee_shielding_key = self.get_tee_shielding_pubkey()?;
+        let operation_call_encrypted =
+            encrypt_with_tee_shielding_pubkey(&tee_shielding_key, &operation_call.encode());
+
+        // compose jsonrpc call
+        let request = Request {
+            shard: sp_core::H256(shard),
+            cyphertext: operation_call_encrypted,
+        };
 
-        Ok(x)
+        // author_submitVCRequest
+        Ok(json_request(
+            "author_submitAndWatchExtrinsic",
+            vec![request.to_hex()],
+        ))
     }
 }
@@ -0,0 +1,196 @@
+use crate::{
+    primitives::crypto::{DirectRequestStatus, RpcReturnValue, TrustedOperationStatus},
+    sidechain::{
+        rpc::{SidechainError, SidechainResult, SidechainSubscription},
+        SidechainResp,
+    },
+    utils::hex::FromHexPrefixed,
+};
+use codec::Decode;
+use sp_core::H256;
+use substrate_api_client::api::Result as ApiResult;
+
+/// The status updates the worker pushes for a trusted operation submitted with
+/// `author_submitAndWatchExtrinsic`, see `DirectCall::watch_di_request`.
+///
+/// The iterator ends after a final status, or once the worker stops watching the operation.
+pub struct TrustedOperationWatcher {
+    subscription: SidechainSubscription,
+    state: WatchState,
+}
+
+impl TrustedOperationWatcher {
+    pub fn new(subscription: SidechainSubscription) -> Self {
+        TrustedOperationWatcher {
+            subscription,
+            state: WatchState::default(),
+        }
+    }
+
+    /// Hash of the trusted operation, known once the worker accepted it.
+    pub fn hash(&self) -> Option<H256> {
+        self.state.hash
+    }
+
+    /// Block until the operation reaches a final status and return it.
+    ///
+    /// Fails with `SidechainError::NotFinal` when the updates end on a status that is not final
+    /// while the worker was still watching the operation.
+    pub fn wait_until_final(mut self) -> ApiResult<TrustedOperationStatus> {
+        for status in &mut self {
+            status?;
+        }
+
+        Ok(self.state.final_status()?)
+    }
+}
+
+impl Iterator for TrustedOperationWatcher {
+    type Item = ApiResult<TrustedOperationStatus>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.state.done {
+            return None;
+        }
+
+        let status = self
+            .subscription
+            .next_message()
+            .and_then(|message| self.state.update(&message));
+        match status {
+            Ok(status) => status.map(Ok),
+            Err(e) => {
+                self.state.done = true;
+                Some(Err(e.into()))
+            }
+        }
+    }
+}
+
+/// What the updates received so far tell about the operation.
+#[derive(Debug, Default)]
+struct WatchState {
+    hash: Option<H256>,
+    last_status: Option<TrustedOperationStatus>,
+    /// The worker sent `do_watch == false`, no more updates follow.
+    unwatched: bool,
+    done: bool,
+}
+
+impl WatchState {
+    /// Decode one update, `None` for a `DirectRequestStatus::Ok` answer.
+    fn update(&mut self, message: &str) -> SidechainResult<Option<TrustedOperationStatus>> {
+        let response: SidechainResp =
+            serde_json::from_str(message).map_err(|e| SidechainError::Parse(e.to_string()))?;
+        let rpc_return_value = RpcReturnValue::from_hex(&response.result)
+            .map_err(|e| SidechainError::Parse(format!("{:?}", e)))?;
+
+        if !rpc_return_value.do_watch {
+            self.unwatched = true;
+            self.done = true;
+        }
+
+        match rpc_return_value.status {
+            DirectRequestStatus::TrustedOperationStatus(status) => {
+                if status == TrustedOperationStatus::Submitted {
+                    self.hash = H256::decode(&mut rpc_return_value.value.as_slice()).ok();
+                }
+                if status.is_final() {
+                    self.done = true;
+                }
+                self.last_status = Some(status.clone());
+                Ok(Some(status))
+            }
+            DirectRequestStatus::Error => {
+                self.done = true;
+                let reason = String::decode(&mut rpc_return_value.value.as_slice())
+                    .unwrap_or_else(|_| hex::encode(&rpc_return_value.value));
+                Err(SidechainError::Rejected(reason))
+            }
+            DirectRequestStatus::Ok => {
+                self.done = true;
+                Ok(None)
+            }
+        }
+    }
+
+    /// The status the updates ended on, if the operation got to it for good.
+    fn final_status(&self) -> SidechainResult<TrustedOperationStatus> {
+        match &self.last_status {
+            Some(status) if status.is_final() || self.unwatched => Ok(status.clone()),
+            Some(status) => Err(SidechainError::NotFinal(format!("{:?}", status))),
+            None => Err(SidechainError::Closed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::hex::ToHexPrefixed;
+    use codec::Encode;
+
+    fn message(status: DirectRequestStatus, value: Vec<u8>, do_watch: bool) -> String {
+        let result = RpcReturnValue::new(value, do_watch, status).to_hex();
+        serde_json::json!({ "id": "1", "jsonrpc": "2.0", "result": result }).to_string()
+    }
+
+    fn top_status(status: TrustedOperationStatus) -> DirectRequestStatus {
+        DirectRequestStatus::TrustedOperationStatus(status)
+    }
+
+    #[test]
+    fn watch_state_works() {
+        let hash = H256::repeat_byte(1);
+        let mut state = WatchState::default();
+
+        let submitted = message(
+            top_status(TrustedOperationStatus::Submitted),
+            hash.encode(),
+            true,
+        );
+        assert_eq!(
+            state.update(&submitted).unwrap(),
+            Some(TrustedOperationStatus::Submitted)
+        );
+        assert_eq!(state.hash, Some(hash));
+        assert!(!state.done);
+
+        let in_block = TrustedOperationStatus::InSidechainBlock(H256::repeat_byte(2));
+        let message = message(top_status(in_block.clone()), vec![], true);
+        assert_eq!(state.update(&message).unwrap(), Some(in_block.clone()));
+        assert!(state.done);
+        assert_eq!(state.final_status().unwrap(), in_block);
+    }
+
+    #[test]
+    fn watch_state_rejected_works() {
+        let mut state = WatchState::default();
+        let rejected = message(DirectRequestStatus::Error, "bad nonce".encode(), false);
+
+        assert_eq!(
+            state.update(&rejected),
+            Err(SidechainError::Rejected("bad nonce".to_string()))
+        );
+        assert!(state.done);
+        assert!(state.update("not json").is_err());
+    }
+
+    #[test]
+    fn watch_state_early_close_works() {
+        let mut state = WatchState::default();
+        assert_eq!(state.final_status(), Err(SidechainError::Closed));
+
+        let ready = message(top_status(TrustedOperationStatus::Ready), vec![], true);
+        state.update(&ready).unwrap();
+        assert_eq!(
+            state.final_status(),
+            Err(SidechainError::NotFinal("Ready".to_string()))
+        );
+
+        // the worker stopped watching, `Ready` is all there is to know
+        let ok = message(DirectRequestStatus::Ok, vec![], false);
+        assert_eq!(state.update(&ok).unwrap(), None);
+        assert_eq!(state.final_status().unwrap(), TrustedOperationStatus::Ready);
+    }
+}
@@ -1,16 +1,26 @@
 use crate::{
-    identity_management::xtbuilder::IdentityManagementXtBuilder,
+    identity_management::{
+        events::{
+            CreateIdentityFailedEvent, DelegateeAddedEvent, IdentityCreatedEvent,
+            IdentityRemovedEvent, IdentityVerifiedEvent, RemoveIdentityFailedEvent,
+            VerifyIdentityFailedEvent,
+        },
+        xtbuilder::IdentityManagementXtBuilder,
+    },
     primitives::{address::Address32, identity::Identity, identity::ValidationData, MrEnclave},
-    ApiClient, SendExtrinsic,
+    ApiClient, ExtrinsicOutcome, SendExtrinsic,
 };
+use rsa::RsaPublicKey;
+use sp_core::H256;
 use substrate_api_client::ac_primitives::Config;
 use substrate_api_client::ac_primitives::{ExtrinsicParams, SignExtrinsic, UncheckedExtrinsicV4};
+use substrate_api_client::api::Result as ApiResult;
 
 use super::IdentityManagementApi;
 
 impl<T> IdentityManagementApi for ApiClient<T>
 where
-    T: Config,
+    T: Config<Hash = H256>,
 {
     type Extrinsic<Call> = UncheckedExtrinsicV4<
         <T::ExtrinsicSigner as SignExtrinsic<T::AccountId>>::ExtrinsicAddress,
@@ -19,9 +29,12 @@ where
         <T::ExtrinsicParams as ExtrinsicParams<T::Index, T::Hash>>::SignedExtra,
     >;
 
-    fn add_delegatee(&self, account: &Address32) {
-        let xt = self.build_extrinsic_add_delegatee(account);
-        self.send_extrinsic(xt);
+    fn add_delegatee(
+        &self,
+        account: &Address32,
+    ) -> ApiResult<ExtrinsicOutcome<DelegateeAddedEvent>> {
+        let xt = self.build_extrinsic_add_delegatee(account)?;
+        self.send_extrinsic_for_event(xt)
     }
 
     fn create_identity(
@@ -30,37 +43,48 @@ where
         address: &Address32,
         identity: &Identity,
         ciphertext_metadata: &Option<Vec<u8>>,
-    ) {
+    ) -> ApiResult<ExtrinsicOutcome<IdentityCreatedEvent>> {
         let xt =
-            self.build_extrinsic_create_identity(shard, address, identity, ciphertext_metadata);
-        self.send_extrinsic(xt);
+            self.build_extrinsic_create_identity(shard, address, identity, ciphertext_metadata)?;
+        self.send_request_extrinsic::<_, _, CreateIdentityFailedEvent>(xt)
     }
 
     fn create_identity_offline(
         &self,
         nonce: u32,
+        tee_shielding_pubkey: &RsaPublicKey,
         shard: &MrEnclave,
         address: &Address32,
         identity: &Identity,
         ciphertext_metadata: &Option<Vec<u8>>,
-    ) {
+    ) -> ApiResult<ExtrinsicOutcome<IdentityCreatedEvent>> {
         let xt = self.build_extrinsic_offline_create_identity(
             nonce,
+            tee_shielding_pubkey,
             shard,
             address,
             identity,
             ciphertext_metadata,
-        );
-        self.send_extrinsic(xt);
+        )?;
+        self.send_request_extrinsic::<_, _, CreateIdentityFailedEvent>(xt)
     }
 
-    fn remove_identity(&self, shard: &MrEnclave, identity: &Identity) {
-        let xt = self.build_extrinsic_remove_identity(shard, identity);
-        self.send_extrinsic(xt);
+    fn remove_identity(
+        &self,
+        shard: &MrEnclave,
+        identity: &Identity,
+    ) -> ApiResult<ExtrinsicOutcome<IdentityRemovedEvent>> {
+        let xt = self.build_extrinsic_remove_identity(shard, identity)?;
+        self.send_request_extrinsic::<_, _, RemoveIdentityFailedEvent>(xt)
     }
 
-    fn verify_identity(&self, shard: &MrEnclave, identity: &Identity, vdata: &ValidationData) {
-        let xt = self.build_extrinsic_verify_identity(shard, identity, vdata);
-        self.send_extrinsic(xt);
+    fn verify_identity(
+        &self,
+        shard: &MrEnclave,
+        identity: &Identity,
+        vdata: &ValidationData,
+    ) -> ApiResult<ExtrinsicOutcome<IdentityVerifiedEvent>> {
+        let xt = self.build_extrinsic_verify_identity(shard, identity, vdata)?;
+        self.send_request_extrinsic::<_, _, VerifyIdentityFailedEvent>(xt)
     }
 }
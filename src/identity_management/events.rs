@@ -1,5 +1,8 @@
 use super::IDENTITY_PALLET_NAME;
-use crate::primitives::{crypto::AesOutput, vc::ErrorDetail, AccountId};
+use crate::{
+    api_client_patch::event::{FilterableEvent, RequestFailed},
+    primitives::{crypto::AesOutput, vc::ErrorDetail, AccountId},
+};
 use codec::Decode;
 use sp_core::H256;
 use substrate_api_client::ac_node_api::StaticEvent;
@@ -16,6 +19,16 @@ impl StaticEvent for SetUserShieldingKeyEvent {
     const EVENT: &'static str = "UserShieldingKeySet";
 }
 
+impl FilterableEvent for SetUserShieldingKeyEvent {
+    fn account(&self) -> Option<&AccountId> {
+        Some(&self.account)
+    }
+
+    fn req_ext_hash(&self) -> Option<H256> {
+        Some(self.req_ext_hash)
+    }
+}
+
 /// SetUserShieldingKeyHandlingFailed
 #[derive(Decode, Debug, PartialEq, Eq)]
 pub struct SetUserShieldingKeyHandlingFailedEvent;
@@ -38,6 +51,16 @@ impl StaticEvent for IdentityCreatedEvent {
     const EVENT: &'static str = "IdentityCreated";
 }
 
+impl FilterableEvent for IdentityCreatedEvent {
+    fn account(&self) -> Option<&AccountId> {
+        Some(&self.who)
+    }
+
+    fn req_ext_hash(&self) -> Option<H256> {
+        Some(self.req_ext_hash)
+    }
+}
+
 /// IdentityRemoved
 #[derive(Decode, Debug)]
 pub struct IdentityRemovedEvent {
@@ -51,6 +74,16 @@ impl StaticEvent for IdentityRemovedEvent {
     const EVENT: &'static str = "IdentityRemoved";
 }
 
+impl FilterableEvent for IdentityRemovedEvent {
+    fn account(&self) -> Option<&AccountId> {
+        Some(&self.who)
+    }
+
+    fn req_ext_hash(&self) -> Option<H256> {
+        Some(self.req_ext_hash)
+    }
+}
+
 /// IdentityVerified
 #[derive(Decode, Debug, PartialEq, Eq)]
 pub struct IdentityVerifiedEvent {
@@ -65,6 +98,16 @@ impl StaticEvent for IdentityVerifiedEvent {
     const EVENT: &'static str = "IdentityVerified";
 }
 
+impl FilterableEvent for IdentityVerifiedEvent {
+    fn account(&self) -> Option<&AccountId> {
+        Some(&self.account)
+    }
+
+    fn req_ext_hash(&self) -> Option<H256> {
+        Some(self.req_ext_hash)
+    }
+}
+
 /// DelegateeAdded
 #[derive(Decode, Debug)]
 pub struct DelegateeAddedEvent {
@@ -76,6 +119,12 @@ impl StaticEvent for DelegateeAddedEvent {
     const EVENT: &'static str = "DelegateeAdded";
 }
 
+impl FilterableEvent for DelegateeAddedEvent {
+    fn account(&self) -> Option<&AccountId> {
+        Some(&self.account)
+    }
+}
+
 /// UnexpectedMessage
 #[derive(Decode, Debug)]
 pub struct UnexpectedMessageEvent;
@@ -92,7 +141,7 @@ impl StaticEvent for IdentityManagementError {
     const EVENT: &'static str = "()";
 }
 
-/// IdentityCreated
+/// CreateIdentityFailed
 #[derive(Decode, Debug)]
 pub struct CreateIdentityFailedEvent {
     pub account: Option<AccountId>,
@@ -102,5 +151,94 @@ pub struct CreateIdentityFailedEvent {
 
 impl StaticEvent for CreateIdentityFailedEvent {
     const PALLET: &'static str = IDENTITY_PALLET_NAME;
-    const EVENT: &'static str = "IdentityCreated";
+    const EVENT: &'static str = "CreateIdentityFailed";
+}
+
+impl FilterableEvent for CreateIdentityFailedEvent {
+    fn account(&self) -> Option<&AccountId> {
+        self.account.as_ref()
+    }
+
+    fn req_ext_hash(&self) -> Option<H256> {
+        Some(self.req_ext_hash)
+    }
+}
+
+impl From<CreateIdentityFailedEvent> for RequestFailed {
+    fn from(event: CreateIdentityFailedEvent) -> Self {
+        RequestFailed {
+            event: CreateIdentityFailedEvent::EVENT,
+            account: event.account,
+            detail: event.detail,
+            req_ext_hash: event.req_ext_hash,
+        }
+    }
+}
+
+/// RemoveIdentityFailed
+#[derive(Decode, Debug)]
+pub struct RemoveIdentityFailedEvent {
+    pub account: Option<AccountId>,
+    pub detail: ErrorDetail,
+    pub req_ext_hash: H256,
+}
+
+impl StaticEvent for RemoveIdentityFailedEvent {
+    const PALLET: &'static str = IDENTITY_PALLET_NAME;
+    const EVENT: &'static str = "RemoveIdentityFailed";
+}
+
+impl FilterableEvent for RemoveIdentityFailedEvent {
+    fn account(&self) -> Option<&AccountId> {
+        self.account.as_ref()
+    }
+
+    fn req_ext_hash(&self) -> Option<H256> {
+        Some(self.req_ext_hash)
+    }
+}
+
+impl From<RemoveIdentityFailedEvent> for RequestFailed {
+    fn from(event: RemoveIdentityFailedEvent) -> Self {
+        RequestFailed {
+            event: RemoveIdentityFailedEvent::EVENT,
+            account: event.account,
+            detail: event.detail,
+            req_ext_hash: event.req_ext_hash,
+        }
+    }
+}
+
+/// VerifyIdentityFailed
+#[derive(Decode, Debug)]
+pub struct VerifyIdentityFailedEvent {
+    pub account: Option<AccountId>,
+    pub detail: ErrorDetail,
+    pub req_ext_hash: H256,
+}
+
+impl StaticEvent for VerifyIdentityFailedEvent {
+    const PALLET: &'static str = IDENTITY_PALLET_NAME;
+    const EVENT: &'static str = "VerifyIdentityFailed";
+}
+
+impl FilterableEvent for VerifyIdentityFailedEvent {
+    fn account(&self) -> Option<&AccountId> {
+        self.account.as_ref()
+    }
+
+    fn req_ext_hash(&self) -> Option<H256> {
+        Some(self.req_ext_hash)
+    }
+}
+
+impl From<VerifyIdentityFailedEvent> for RequestFailed {
+    fn from(event: VerifyIdentityFailedEvent) -> Self {
+        RequestFailed {
+            event: VerifyIdentityFailedEvent::EVENT,
+            account: event.account,
+            detail: event.detail,
+            req_ext_hash: event.req_ext_hash,
+        }
+    }
 }
@@ -1,8 +1,16 @@
-use crate::primitives::{
-    address::Address32,
-    identity::{Identity, ValidationData},
-    MrEnclave,
+use crate::{
+    primitives::{
+        address::Address32,
+        identity::{Identity, ValidationData},
+        MrEnclave,
+    },
+    ExtrinsicOutcome,
 };
+use events::{
+    DelegateeAddedEvent, IdentityCreatedEvent, IdentityRemovedEvent, IdentityVerifiedEvent,
+};
+use rsa::RsaPublicKey;
+use substrate_api_client::api::Result as ApiResult;
 
 pub mod api;
 pub mod events;
@@ -10,25 +18,42 @@ pub mod xtbuilder;
 
 pub const IDENTITY_PALLET_NAME: &str = "IdentityManagement";
 
+/// Requests that go through the TEE worker wait for the pallet event answering them.
+/// A `*Failed` answer is returned as an `ApiClientError::Other` wrapping a `RequestFailed`.
 pub trait IdentityManagementApi {
     type Extrinsic<Call>;
 
-    fn add_delegatee(&self, account: &Address32);
+    fn add_delegatee(
+        &self,
+        account: &Address32,
+    ) -> ApiResult<ExtrinsicOutcome<DelegateeAddedEvent>>;
     fn create_identity(
         &self,
         shard: &MrEnclave,
         address: &Address32,
         identity: &Identity,
         ciphertext_metadata: &Option<Vec<u8>>,
-    );
+    ) -> ApiResult<ExtrinsicOutcome<IdentityCreatedEvent>>;
+    /// `create_identity` signed at `nonce` and encrypted with `tee_shielding_pubkey`: neither is
+    /// queried from the parachain, only the extrinsic is submitted.
     fn create_identity_offline(
         &self,
         nonce: u32,
+        tee_shielding_pubkey: &RsaPublicKey,
         shard: &MrEnclave,
         address: &Address32,
         identity: &Identity,
         ciphertext_metadata: &Option<Vec<u8>>,
-    );
-    fn remove_identity(&self, shard: &MrEnclave, identity: &Identity);
-    fn verify_identity(&self, shard: &MrEnclave, identity: &Identity, vdata: &ValidationData);
+    ) -> ApiResult<ExtrinsicOutcome<IdentityCreatedEvent>>;
+    fn remove_identity(
+        &self,
+        shard: &MrEnclave,
+        identity: &Identity,
+    ) -> ApiResult<ExtrinsicOutcome<IdentityRemovedEvent>>;
+    fn verify_identity(
+        &self,
+        shard: &MrEnclave,
+        identity: &Identity,
+        vdata: &ValidationData,
+    ) -> ApiResult<ExtrinsicOutcome<IdentityVerifiedEvent>>;
 }
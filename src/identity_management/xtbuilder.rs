@@ -13,7 +13,8 @@ use codec::Encode;
 use rsa::RsaPublicKey;
 use sp_core::H256;
 use substrate_api_client::{
-    ac_compose_macros::{compose_call, compose_extrinsic, compose_extrinsic_offline},
+    ac_compose_macros::compose_call,
+    ac_node_api::Metadata,
     ac_primitives::{
         extrinsics::CallIndex, Config, ExtrinsicParams, SignExtrinsic, UncheckedExtrinsicV4,
     },
@@ -28,7 +29,10 @@ pub type VerifyIdentityFn = (CallIndex, H256, Vec<u8>, Vec<u8>);
 pub trait IdentityManagementXtBuilder {
     type Extrinsic<Call>;
 
-    fn build_extrinsic_add_delegatee(&self, account: &Address32) -> Self::Extrinsic<AddDelegateFn>;
+    fn build_extrinsic_add_delegatee(
+        &self,
+        account: &Address32,
+    ) -> ApiResult<Self::Extrinsic<AddDelegateFn>>;
 
     fn build_extrinsic_create_identity(
         &self,
@@ -36,29 +40,32 @@ pub trait IdentityManagementXtBuilder {
         address: &Address32,
         identity: &Identity,
         ciphertext_metadata: &Option<Vec<u8>>,
-    ) -> Self::Extrinsic<CreateIdentityFn>;
+    ) -> ApiResult<Self::Extrinsic<CreateIdentityFn>>;
 
+    /// Same as `build_extrinsic_create_identity`, but signed at `nonce` and encrypted with the
+    /// given `tee_shielding_pubkey`, so nothing is queried from the parachain.
     fn build_extrinsic_offline_create_identity(
         &self,
         nonce: u32,
+        tee_shielding_pubkey: &RsaPublicKey,
         shard: &MrEnclave,
         address: &Address32,
         identity: &Identity,
         ciphertext_metadata: &Option<Vec<u8>>,
-    ) -> Self::Extrinsic<CreateIdentityFn>;
+    ) -> ApiResult<Self::Extrinsic<CreateIdentityFn>>;
 
     fn build_extrinsic_remove_identity(
         &self,
         shard: &MrEnclave,
         identity: &Identity,
-    ) -> Self::Extrinsic<RemoveIdentityFn>;
+    ) -> ApiResult<Self::Extrinsic<RemoveIdentityFn>>;
 
     fn build_extrinsic_verify_identity(
         &self,
         shard: &MrEnclave,
         identity: &Identity,
         validation_data: &ValidationData,
-    ) -> Self::Extrinsic<VerifyIdentityFn>;
+    ) -> ApiResult<Self::Extrinsic<VerifyIdentityFn>>;
 
     fn encrypt_identity_with_tee_shielding_key(
         tee_shielding_pubkey: RsaPublicKey,
@@ -88,9 +95,12 @@ where
         Ok(encrypted_identity)
     }
 
-    fn build_extrinsic_add_delegatee(&self, account: &Address32) -> Self::Extrinsic<AddDelegateFn> {
-        // compose_extrinsic!(self.api, IDENTITY_PALLET_NAME, "add_delegatee", *account)
-        todo!()
+    fn build_extrinsic_add_delegatee(
+        &self,
+        account: &Address32,
+    ) -> ApiResult<Self::Extrinsic<AddDelegateFn>> {
+        let call = compose_call_add_delegatee(self.api.metadata(), account);
+        self.compose_extrinsic(call)
     }
 
     fn build_extrinsic_create_identity(
@@ -99,77 +109,56 @@ where
         address: &Address32,
         identity: &Identity,
         ciphertext_metadata: &Option<Vec<u8>>,
-    ) -> Self::Extrinsic<CreateIdentityFn> {
-        let identity_encoded = identity.encode();
-        let tee_shielding_pubkey = self.get_tee_shielding_pubkey().unwrap();
+    ) -> ApiResult<Self::Extrinsic<CreateIdentityFn>> {
+        let tee_shielding_pubkey = self.get_tee_shielding_pubkey()?;
         let encrypted_identity =
-            encrypt_with_tee_shielding_pubkey(&tee_shielding_pubkey, &identity_encoded);
+            Self::encrypt_identity_with_tee_shielding_key(tee_shielding_pubkey, identity.clone())?;
 
-        // compose_extrinsic!(
-        //     self.api,
-        //     IDENTITY_PALLET_NAME,
-        //     "create_identity",
-        //     H256::from(shard),
-        //     *address,
-        //     encrypted_identity,
-        //     ciphertext_metadata.clone()
-        // )
-
-        todo!()
+        let call = compose_call_create_identity(
+            self.api.metadata(),
+            shard,
+            address,
+            encrypted_identity,
+            ciphertext_metadata,
+        );
+        self.compose_extrinsic(call)
     }
 
     fn build_extrinsic_offline_create_identity(
         &self,
         nonce: u32,
+        tee_shielding_pubkey: &RsaPublicKey,
         shard: &MrEnclave,
         address: &Address32,
         identity: &Identity,
         ciphertext_metadata: &Option<Vec<u8>>,
-    ) -> Self::Extrinsic<CreateIdentityFn> {
-        let identity_encoded = identity.encode();
-        let tee_shielding_pubkey = self.get_tee_shielding_pubkey().unwrap();
-        let encrypted_identity =
-            encrypt_with_tee_shielding_pubkey(&tee_shielding_pubkey, &identity_encoded);
+    ) -> ApiResult<Self::Extrinsic<CreateIdentityFn>> {
+        let encrypted_identity = Self::encrypt_identity_with_tee_shielding_key(
+            tee_shielding_pubkey.clone(),
+            identity.clone(),
+        )?;
 
-        let meta = self.api.metadata();
-        let call = compose_call!(
-            meta,
-            IDENTITY_PALLET_NAME,
-            "create_identity",
-            H256::from(shard),
-            *address,
+        let call = compose_call_create_identity(
+            self.api.metadata(),
+            shard,
+            address,
             encrypted_identity,
-            ciphertext_metadata.clone()
+            ciphertext_metadata,
         );
-
-        // compose_extrinsic_offline!(
-        //     self.api.signer().unwrap(),
-        //     call,
-        //     self.api.extrinsic_params(nonce.into())
-        // )
-
-        todo!()
+        self.compose_extrinsic_offline(call, nonce.into())
     }
 
     fn build_extrinsic_remove_identity(
         &self,
         shard: &MrEnclave,
         identity: &Identity,
-    ) -> Self::Extrinsic<RemoveIdentityFn> {
-        let identity_encoded = identity.encode();
-        let tee_shielding_pubkey = self.get_tee_shielding_pubkey().unwrap();
+    ) -> ApiResult<Self::Extrinsic<RemoveIdentityFn>> {
+        let tee_shielding_pubkey = self.get_tee_shielding_pubkey()?;
         let encrypted_identity =
-            encrypt_with_tee_shielding_pubkey(&tee_shielding_pubkey, &identity_encoded);
-
-        // compose_extrinsic!(
-        //     self.api,
-        //     IDENTITY_PALLET_NAME,
-        //     "remove_identity",
-        //     H256::from(shard),
-        //     encrypted_identity
-        // )
+            Self::encrypt_identity_with_tee_shielding_key(tee_shielding_pubkey, identity.clone())?;
 
-        todo!()
+        let call = compose_call_remove_identity(self.api.metadata(), shard, encrypted_identity);
+        self.compose_extrinsic(call)
     }
 
     fn build_extrinsic_verify_identity(
@@ -177,8 +166,8 @@ where
         shard: &MrEnclave,
         identity: &Identity,
         validation_data: &ValidationData,
-    ) -> Self::Extrinsic<VerifyIdentityFn> {
-        let tee_shielding_pubkey = self.get_tee_shielding_pubkey().unwrap();
+    ) -> ApiResult<Self::Extrinsic<VerifyIdentityFn>> {
+        let tee_shielding_pubkey = self.get_tee_shielding_pubkey()?;
 
         let identity_encoded = identity.encode();
         let encrypted_identity =
@@ -187,15 +176,145 @@ where
         let encrypted_validation_data =
             encrypt_with_tee_shielding_pubkey(&tee_shielding_pubkey, &validation_data_encoded);
 
-        // compose_extrinsic!(
-        //     self.api,
-        //     IDENTITY_PALLET_NAME,
-        //     "verify_identity",
-        //     H256::from(shard),
-        //     encrypted_identity,
-        //     encrypted_validation_data
-        // )
+        let call = compose_call_verify_identity(
+            self.api.metadata(),
+            shard,
+            encrypted_identity,
+            encrypted_validation_data,
+        );
+        self.compose_extrinsic(call)
+    }
+}
+
+/// IdentityManagement calls, composed from the call indexes in the runtime `metadata`.
+/// Identities and validation data are expected to be encrypted with the TEE shielding key already.
+pub fn compose_call_add_delegatee(metadata: &Metadata, account: &Address32) -> AddDelegateFn {
+    compose_call!(metadata, IDENTITY_PALLET_NAME, "add_delegatee", *account)
+}
+
+pub fn compose_call_create_identity(
+    metadata: &Metadata,
+    shard: &MrEnclave,
+    address: &Address32,
+    encrypted_identity: Vec<u8>,
+    ciphertext_metadata: &Option<Vec<u8>>,
+) -> CreateIdentityFn {
+    compose_call!(
+        metadata,
+        IDENTITY_PALLET_NAME,
+        "create_identity",
+        H256::from(shard),
+        *address,
+        encrypted_identity,
+        ciphertext_metadata.clone()
+    )
+}
+
+pub fn compose_call_remove_identity(
+    metadata: &Metadata,
+    shard: &MrEnclave,
+    encrypted_identity: Vec<u8>,
+) -> RemoveIdentityFn {
+    compose_call!(
+        metadata,
+        IDENTITY_PALLET_NAME,
+        "remove_identity",
+        H256::from(shard),
+        encrypted_identity
+    )
+}
+
+pub fn compose_call_verify_identity(
+    metadata: &Metadata,
+    shard: &MrEnclave,
+    encrypted_identity: Vec<u8>,
+    encrypted_validation_data: Vec<u8>,
+) -> VerifyIdentityFn {
+    compose_call!(
+        metadata,
+        IDENTITY_PALLET_NAME,
+        "verify_identity",
+        H256::from(shard),
+        encrypted_identity,
+        encrypted_validation_data
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::mock_metadata::{
+        mock_metadata, IdentityManagementCall, IDENTITY_PALLET_INDEX,
+    };
+
+    #[test]
+    fn compose_call_add_delegatee_works() {
+        let metadata = mock_metadata();
+        let account = Address32::from([1u8; 32]);
+
+        let call = compose_call_add_delegatee(&metadata, &account);
+
+        let mut expected = vec![IDENTITY_PALLET_INDEX, 0];
+        expected.extend_from_slice(&[1u8; 32]);
+        assert_eq!(call.encode(), expected);
+    }
+
+    #[test]
+    fn compose_call_create_identity_works() {
+        let metadata = mock_metadata();
+        let shard = [7u8; 32];
+        let account = Address32::from([1u8; 32]);
+        let encrypted_identity = vec![0xaa, 0xbb];
+        let ciphertext_metadata = Some(vec![0xcc]);
+
+        let call = compose_call_create_identity(
+            &metadata,
+            &shard,
+            &account,
+            encrypted_identity.clone(),
+            &ciphertext_metadata,
+        );
+
+        let mut expected = vec![IDENTITY_PALLET_INDEX, 3];
+        expected.extend_from_slice(&shard);
+        expected.extend_from_slice(&[1u8; 32]);
+        expected.extend_from_slice(&[8, 0xaa, 0xbb]);
+        expected.extend_from_slice(&[1, 4, 0xcc]);
+        assert_eq!(call.encode(), expected);
+
+        let runtime_call = IdentityManagementCall::create_identity {
+            shard: H256::from(shard),
+            user: account,
+            encrypted_identity,
+            encrypted_metadata: ciphertext_metadata,
+        };
+        assert_eq!(call.encode()[1..], runtime_call.encode()[..]);
+    }
+
+    #[test]
+    fn compose_call_remove_identity_works() {
+        let metadata = mock_metadata();
+        let shard = [7u8; 32];
+
+        let call = compose_call_remove_identity(&metadata, &shard, vec![0xaa]);
+
+        let mut expected = vec![IDENTITY_PALLET_INDEX, 4];
+        expected.extend_from_slice(&shard);
+        expected.extend_from_slice(&[4, 0xaa]);
+        assert_eq!(call.encode(), expected);
+    }
+
+    #[test]
+    fn compose_call_verify_identity_works() {
+        let metadata = mock_metadata();
+        let shard = [7u8; 32];
+
+        let call = compose_call_verify_identity(&metadata, &shard, vec![0xaa], vec![0xbb, 0xcc]);
 
-        todo!()
+        let mut expected = vec![IDENTITY_PALLET_INDEX, 5];
+        expected.extend_from_slice(&shard);
+        expected.extend_from_slice(&[4, 0xaa]);
+        expected.extend_from_slice(&[8, 0xbb, 0xcc]);
+        assert_eq!(call.encode(), expected);
     }
 }
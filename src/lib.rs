@@ -13,105 +13,262 @@ pub mod sidechain;
 pub mod utils;
 pub mod vc_management;
 
-use std::marker::PhantomData;
-
+use api_client_patch::event::{FilterableEvent, RequestFailed, SubscribeEventPatch};
 use codec::Encode;
+use direct_call::nonce::NonceCache;
 use sidechain::rpc::SidechainRpcClient;
-use sp_core::{crypto::AccountId32 as AccountId, sr25519};
+use sp_core::{crypto::AccountId32 as AccountId, sr25519, H256};
 use sp_runtime::MultiAddress;
+use std::time::Duration;
 use substrate_api_client::{
-    ac_primitives::{
-        Config, DefaultRuntimeConfig, ExtrinsicParams, ExtrinsicSigner, SignExtrinsic,
-        UncheckedExtrinsicV4,
-    },
-    api::Result as ApiResult,
-    rpc::{JsonrpseeClient, WsRpcClient},
-    Api, SubmitAndWatch, XtStatus,
+    ac_compose_macros::compose_extrinsic_offline,
+    ac_node_api::StaticEvent,
+    ac_primitives::{Config, ExtrinsicParams, SignExtrinsic, UncheckedExtrinsicV4},
+    api::{Error as ApiClientError, Result as ApiResult},
+    rpc::WsRpcClient,
+    Api, ExtrinsicReport, SubmitAndWatchUntilSuccess,
 };
 
 pub type Address = MultiAddress<AccountId, ()>;
 
-// #[cfg(feature = "local")]
-// const NODE_URL: &str = "ws://127.0.0.1:9944";
-// #[cfg(feature = "local")]
-// const WORKER_URL: &str = "wss://localhost:2000";
-
-// Default to `local` worker mode when no cargo features are set.
-// #[cfg(not(any(feature = "local", feature = "staging", feature = "prod2")))]
-// const NODE_URL: &str = "ws://127.0.0.1:9944";
-// #[cfg(not(any(feature = "local", feature = "staging", feature = "prod2")))]
-// const WORKER_URL: &str = "wss://localhost:2000";
+/// An extrinsic signed by the api signer of an `ApiClient<T>`.
+pub type ParachainExtrinsic<T, Call> = UncheckedExtrinsicV4<
+    <<T as Config>::ExtrinsicSigner as SignExtrinsic<<T as Config>::AccountId>>::ExtrinsicAddress,
+    Call,
+    <<T as Config>::ExtrinsicSigner as SignExtrinsic<<T as Config>::AccountId>>::Signature,
+    <<T as Config>::ExtrinsicParams as ExtrinsicParams<
+        <T as Config>::Index,
+        <T as Config>::Hash,
+    >>::SignedExtra,
+>;
 
+/// Parachain and TEE worker endpoints an `ApiClient` connects to.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct EnpointConfig {
     pub parachain_endpoint: String,
     pub worker_endpoint: String,
 }
 
+impl EnpointConfig {
+    pub fn new(parachain_endpoint: &str, worker_endpoint: &str) -> Self {
+        EnpointConfig {
+            parachain_endpoint: parachain_endpoint.to_string(),
+            worker_endpoint: worker_endpoint.to_string(),
+        }
+    }
+
+    /// A parachain and worker launched on the local machine.
+    pub fn local() -> Self {
+        Self::new("ws://127.0.0.1:9944", "wss://localhost:2000")
+    }
+
+    pub fn prod2() -> Self {
+        Self::new(
+            "wss://tee-internal.litentry.io:443",
+            "wss://tee-internal.litentry.io:2000",
+        )
+    }
+}
+
+/// The preset selected by the `local` or `prod2` cargo feature.
+/// Default to `local` worker mode when no cargo features are set.
+///
+/// There is no preset for `staging`, its endpoints are not published: pass them to
+/// `EnpointConfig::new` instead.
+impl Default for EnpointConfig {
+    fn default() -> Self {
+        if cfg!(feature = "prod2") {
+            Self::prod2()
+        } else {
+            Self::local()
+        }
+    }
+}
+
+/// How long to wait for the events answering an extrinsic by default.
+pub const DEFAULT_EVENT_TIMEOUT: Duration = Duration::from_secs(120);
+
 pub struct ApiClient<T>
 where
     T: Config,
 {
-    pub api: Api<DefaultRuntimeConfig, WsRpcClient>,
+    pub api: Api<T, WsRpcClient>,
     pub sidechain: SidechainRpcClient,
-    phantom: PhantomData<T>,
+    /// Upper bound on waiting for pallet events, see `SubscribeEventPatch`.
+    pub event_timeout: Duration,
+    /// Trusted-call nonces, see `NonceManager`.
+    pub nonces: NonceCache,
 }
 
 impl<T> ApiClient<T>
 where
     T: Config,
+    T::ExtrinsicSigner: From<sr25519::Pair>,
 {
-    pub fn new_with_signer(signer: sr25519::Pair) -> ApiResult<Self> {
-        let t_u = "wss://tee-internal.litentry.io:443";
-        let t_x = "wss://tee-internal.litentry.io:2000";
-
-        let client = WsRpcClient::new(t_u)?;
-        let mut api = Api::<DefaultRuntimeConfig, WsRpcClient>::new(client)?;
-
-        let signer = ExtrinsicSigner::new(signer);
-        api.set_signer(signer);
+    pub fn new(config: &EnpointConfig, signer: sr25519::Pair) -> ApiResult<Self> {
+        let client = WsRpcClient::new(&config.parachain_endpoint)?;
+        let mut api = Api::<T, WsRpcClient>::new(client)?;
+        api.set_signer(signer.into());
 
-        let sidechain = SidechainRpcClient::new(t_x);
+        let sidechain = SidechainRpcClient::new(&config.worker_endpoint);
 
-        println!("[+] Parachain rpc : {}", t_u);
-        println!("[+] Sidechain rpc : {}", t_x);
+        log::info!("[+] Parachain rpc : {}", config.parachain_endpoint);
+        log::info!("[+] Sidechain rpc : {}", config.worker_endpoint);
 
         Ok(ApiClient {
             api,
             sidechain,
-            phantom: PhantomData,
+            event_timeout: DEFAULT_EVENT_TIMEOUT,
+            nonces: NonceCache::default(),
         })
     }
 
+    /// Connect to the endpoints of the preset selected by cargo features, see `EnpointConfig::default`.
+    pub fn new_with_signer(signer: sr25519::Pair) -> ApiResult<Self> {
+        Self::new(&EnpointConfig::default(), signer)
+    }
+}
+
+impl<T> ApiClient<T>
+where
+    T: Config,
+{
     pub fn get_signer(&self) -> Option<&T::AccountId> {
         // self.api.signer_account()
         todo!()
     }
+
+    /// Sign `call` with the api signer at the next nonce of the signer account.
+    pub fn compose_extrinsic<Call: Encode + Clone>(
+        &self,
+        call: Call,
+    ) -> ApiResult<ParachainExtrinsic<T, Call>> {
+        let nonce = self.api.get_nonce()?;
+        self.compose_extrinsic_offline(call, nonce)
+    }
+
+    /// Sign `call` with the api signer at an explicit `nonce`, without querying the parachain.
+    pub fn compose_extrinsic_offline<Call: Encode + Clone>(
+        &self,
+        call: Call,
+        nonce: T::Index,
+    ) -> ApiResult<ParachainExtrinsic<T, Call>> {
+        let signer = self.api.signer().ok_or(ApiClientError::NoSigner)?;
+        Ok(compose_extrinsic_offline!(
+            signer,
+            call,
+            self.api.extrinsic_params(nonce)
+        ))
+    }
+}
+
+/// What an extrinsic sent to the parachain led to.
+#[derive(Debug)]
+pub struct ExtrinsicOutcome<Event> {
+    /// Hash of the extrinsic, the `req_ext_hash` of events answering a TEE request.
+    pub extrinsic_hash: H256,
+    /// Block in which `event` was emitted.
+    pub block_hash: H256,
+    pub event: Event,
 }
 
 pub trait SendExtrinsic {
     type Extrinsic<Call>;
 
-    fn send_extrinsic<Call: Encode + Clone>(&self, extrinsic: Self::Extrinsic<Call>);
+    /// Submit `extrinsic` and wait until it is successfully included in a block.
+    fn send_extrinsic<Call: Encode + Clone>(
+        &self,
+        extrinsic: Self::Extrinsic<Call>,
+    ) -> ApiResult<ExtrinsicReport<H256>>;
+
+    /// Submit `extrinsic` and take `Event` from the events of the block it was included in.
+    fn send_extrinsic_for_event<Call: Encode + Clone, Event: StaticEvent>(
+        &self,
+        extrinsic: Self::Extrinsic<Call>,
+    ) -> ApiResult<ExtrinsicOutcome<Event>>;
+
+    /// Submit the request `extrinsic` and wait until the TEE worker answers it with either
+    /// `Success` or `Failed`, correlated by the `req_ext_hash` of the events.
+    fn send_request_extrinsic<Call, Success, Failed>(
+        &self,
+        extrinsic: Self::Extrinsic<Call>,
+    ) -> ApiResult<ExtrinsicOutcome<Success>>
+    where
+        Call: Encode + Clone,
+        Success: FilterableEvent,
+        Failed: FilterableEvent + Into<RequestFailed>;
 }
 
 impl<T> SendExtrinsic for ApiClient<T>
 where
-    T: Config,
+    T: Config<Hash = H256>,
 {
-    type Extrinsic<Call> = UncheckedExtrinsicV4<
-        <T::ExtrinsicSigner as SignExtrinsic<T::AccountId>>::ExtrinsicAddress,
-        Call,
-        <T::ExtrinsicSigner as SignExtrinsic<T::AccountId>>::Signature,
-        <T::ExtrinsicParams as ExtrinsicParams<T::Index, T::Hash>>::SignedExtra,
-    >;
-
-    fn send_extrinsic<Call: Encode + Clone>(&self, extrinsic: Self::Extrinsic<Call>) {
-        match self
+    type Extrinsic<Call> = ParachainExtrinsic<T, Call>;
+
+    fn send_extrinsic<Call: Encode + Clone>(
+        &self,
+        extrinsic: Self::Extrinsic<Call>,
+    ) -> ApiResult<ExtrinsicReport<H256>> {
+        let report = self
             .api
-            .submit_and_watch_extrinsic_until(extrinsic, XtStatus::InBlock)
-        {
-            Ok(tx_hash) => println!(" ✅ Transaction got included. Hash: {:?}", tx_hash),
-            Err(e) => println!(" ❌ Transaction error : {:?}", e),
+            .submit_and_watch_extrinsic_until_success(extrinsic, false)?;
+        log::debug!(
+            "Transaction got included. Hash: {:?}",
+            report.extrinsic_hash
+        );
+
+        Ok(report)
+    }
+
+    fn send_extrinsic_for_event<Call: Encode + Clone, Event: StaticEvent>(
+        &self,
+        extrinsic: Self::Extrinsic<Call>,
+    ) -> ApiResult<ExtrinsicOutcome<Event>> {
+        let report = self.send_extrinsic(extrinsic)?;
+        let block_hash = report.block_hash.ok_or_else(|| {
+            ApiClientError::Other("[+] extrinsic report without block hash".to_string().into())
+        })?;
+
+        let mut event = None;
+        for event_details in report.events.iter().flatten() {
+            event = event_details.as_event::<Event>()?;
+            if event.is_some() {
+                break;
+            }
         }
+        let event = event.ok_or_else(|| {
+            ApiClientError::Other(
+                format!("[+] {}.{} not found", Event::PALLET, Event::EVENT).into(),
+            )
+        })?;
+
+        Ok(ExtrinsicOutcome {
+            extrinsic_hash: report.extrinsic_hash,
+            block_hash,
+            event,
+        })
+    }
+
+    fn send_request_extrinsic<Call, Success, Failed>(
+        &self,
+        extrinsic: Self::Extrinsic<Call>,
+    ) -> ApiResult<ExtrinsicOutcome<Success>>
+    where
+        Call: Encode + Clone,
+        Success: FilterableEvent,
+        Failed: FilterableEvent + Into<RequestFailed>,
+    {
+        // Subscribe before submitting, the worker may answer in the very next block.
+        let mut subscription = self.subscribe_event_receiver()?;
+        let report = self.send_extrinsic(extrinsic)?;
+
+        let (block_hash, event) =
+            self.wait_request_event::<Success, Failed>(&mut subscription, &report.extrinsic_hash)?;
+
+        Ok(ExtrinsicOutcome {
+            extrinsic_hash: report.extrinsic_hash,
+            block_hash,
+            event,
+        })
     }
 }
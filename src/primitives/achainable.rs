@@ -0,0 +1,660 @@
+// Copyright 2020-2023 Litentry Technologies GmbH.
+// This file is part of Litentry.
+//
+// Litentry is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Litentry is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
+
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+use thiserror::Error;
+
+use super::{
+    assertion::{
+        AchainableAmount, AchainableAmountHolding, AchainableAmountToken, AchainableAmounts,
+        AchainableBasic, AchainableBetweenPercents, AchainableClassOfYear, AchainableDate,
+        AchainableDateInterval, AchainableDatePercent, AchainableParams, AchainableToken,
+        Assertion, ParameterString,
+    },
+    network::Web3Network,
+    params::{parameter_string, AssertionError},
+};
+
+use AchainableParam as P;
+use Web3Network as N;
+
+/// Which `AchainableParams` variant an Achainable assertion takes.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, EnumIter)]
+pub enum AchainableKind {
+    AmountHolding,
+    AmountToken,
+    Amount,
+    Amounts,
+    Basic,
+    BetweenPercents,
+    ClassOfYear,
+    DateInterval,
+    DatePercent,
+    Date,
+    Token,
+}
+
+/// A parameter of the `AchainableParams` structs, besides `name` and `chain`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, EnumIter)]
+pub enum AchainableParam {
+    Amount,
+    Amount1,
+    Amount2,
+    Date,
+    Date1,
+    Date2,
+    StartDate,
+    EndDate,
+    Token,
+    Percent,
+    GreaterThanOrEqualTo,
+    LessThanOrEqualTo,
+}
+
+impl AchainableParam {
+    /// The field name in the `AchainableParams` structs.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Amount => "amount",
+            Self::Amount1 => "amount1",
+            Self::Amount2 => "amount2",
+            Self::Date => "date",
+            Self::Date1 => "date1",
+            Self::Date2 => "date2",
+            Self::StartDate => "start_date",
+            Self::EndDate => "end_date",
+            Self::Token => "token",
+            Self::Percent => "percent",
+            Self::GreaterThanOrEqualTo => "greater_than_or_equal_to",
+            Self::LessThanOrEqualTo => "less_than_or_equal_to",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::iter().find(|param| param.name() == name)
+    }
+}
+
+impl AchainableKind {
+    /// The name of the `AchainableParams` variant.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::AmountHolding => "AmountHolding",
+            Self::AmountToken => "AmountToken",
+            Self::Amount => "Amount",
+            Self::Amounts => "Amounts",
+            Self::Basic => "Basic",
+            Self::BetweenPercents => "BetweenPercents",
+            Self::ClassOfYear => "ClassOfYear",
+            Self::DateInterval => "DateInterval",
+            Self::DatePercent => "DatePercent",
+            Self::Date => "Date",
+            Self::Token => "Token",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::iter().find(|kind| kind.name() == name)
+    }
+
+    /// Every parameter of the variant, optional ones included.
+    pub fn params(&self) -> &'static [AchainableParam] {
+        use AchainableParam::*;
+        match self {
+            Self::AmountHolding => &[Amount, Date, Token],
+            Self::AmountToken => &[Amount, Token],
+            Self::Amount => &[Amount],
+            Self::Amounts => &[Amount1, Amount2],
+            Self::Basic => &[],
+            Self::BetweenPercents => &[GreaterThanOrEqualTo, LessThanOrEqualTo],
+            Self::ClassOfYear => &[Date1, Date2],
+            Self::DateInterval => &[StartDate, EndDate],
+            Self::DatePercent => &[Token, Date, Percent],
+            Self::Date => &[Date],
+            Self::Token => &[Token],
+        }
+    }
+}
+
+impl AchainableParams {
+    pub fn kind(&self) -> AchainableKind {
+        match self {
+            AchainableParams::AmountHolding(..) => AchainableKind::AmountHolding,
+            AchainableParams::AmountToken(..) => AchainableKind::AmountToken,
+            AchainableParams::Amount(..) => AchainableKind::Amount,
+            AchainableParams::Amounts(..) => AchainableKind::Amounts,
+            AchainableParams::Basic(..) => AchainableKind::Basic,
+            AchainableParams::BetweenPercents(..) => AchainableKind::BetweenPercents,
+            AchainableParams::ClassOfYear(..) => AchainableKind::ClassOfYear,
+            AchainableParams::DateInterval(..) => AchainableKind::DateInterval,
+            AchainableParams::DatePercent(..) => AchainableKind::DatePercent,
+            AchainableParams::Date(..) => AchainableKind::Date,
+            AchainableParams::Token(..) => AchainableKind::Token,
+        }
+    }
+
+    pub fn chain(&self) -> ParameterString {
+        match self {
+            AchainableParams::AmountHolding(p) => p.chain.clone(),
+            AchainableParams::AmountToken(p) => p.chain.clone(),
+            AchainableParams::Amount(p) => p.chain.clone(),
+            AchainableParams::Amounts(p) => p.chain.clone(),
+            AchainableParams::Basic(p) => p.chain.clone(),
+            AchainableParams::BetweenPercents(p) => p.chain.clone(),
+            AchainableParams::ClassOfYear(p) => p.chain.clone(),
+            AchainableParams::DateInterval(p) => p.chain.clone(),
+            AchainableParams::DatePercent(p) => p.chain.clone(),
+            AchainableParams::Date(p) => p.chain.clone(),
+            AchainableParams::Token(p) => p.chain.clone(),
+        }
+    }
+
+    /// The `kind` variant with `params`, which must give every parameter of the variant but
+    /// an optional `token`. Returns the first missing parameter otherwise, `params` the
+    /// variant doesn't take are ignored.
+    pub fn new(
+        kind: AchainableKind,
+        name: ParameterString,
+        chain: ParameterString,
+        params: &[(AchainableParam, ParameterString)],
+    ) -> Result<Self, AchainableParam> {
+        let optional = |param: AchainableParam| {
+            params
+                .iter()
+                .find(|(p, _)| *p == param)
+                .map(|(_, value)| value.clone())
+        };
+        let required = |param: AchainableParam| optional(param).ok_or(param);
+
+        Ok(match kind {
+            AchainableKind::AmountHolding => {
+                AchainableParams::AmountHolding(AchainableAmountHolding {
+                    name,
+                    chain,
+                    amount: required(P::Amount)?,
+                    date: required(P::Date)?,
+                    token: optional(P::Token),
+                })
+            }
+            AchainableKind::AmountToken => AchainableParams::AmountToken(AchainableAmountToken {
+                name,
+                chain,
+                amount: required(P::Amount)?,
+                token: optional(P::Token),
+            }),
+            AchainableKind::Amount => AchainableParams::Amount(AchainableAmount {
+                name,
+                chain,
+                amount: required(P::Amount)?,
+            }),
+            AchainableKind::Amounts => AchainableParams::Amounts(AchainableAmounts {
+                name,
+                chain,
+                amount1: required(P::Amount1)?,
+                amount2: required(P::Amount2)?,
+            }),
+            AchainableKind::Basic => AchainableParams::Basic(AchainableBasic { name, chain }),
+            AchainableKind::BetweenPercents => {
+                AchainableParams::BetweenPercents(AchainableBetweenPercents {
+                    name,
+                    chain,
+                    greater_than_or_equal_to: required(P::GreaterThanOrEqualTo)?,
+                    less_than_or_equal_to: required(P::LessThanOrEqualTo)?,
+                })
+            }
+            AchainableKind::ClassOfYear => AchainableParams::ClassOfYear(AchainableClassOfYear {
+                name,
+                chain,
+                date1: required(P::Date1)?,
+                date2: required(P::Date2)?,
+            }),
+            AchainableKind::DateInterval => {
+                AchainableParams::DateInterval(AchainableDateInterval {
+                    name,
+                    chain,
+                    start_date: required(P::StartDate)?,
+                    end_date: required(P::EndDate)?,
+                })
+            }
+            AchainableKind::DatePercent => AchainableParams::DatePercent(AchainableDatePercent {
+                name,
+                chain,
+                token: required(P::Token)?,
+                date: required(P::Date)?,
+                percent: required(P::Percent)?,
+            }),
+            AchainableKind::Date => AchainableParams::Date(AchainableDate {
+                name,
+                chain,
+                date: required(P::Date)?,
+            }),
+            AchainableKind::Token => AchainableParams::Token(AchainableToken {
+                name,
+                chain,
+                token: required(P::Token)?,
+            }),
+        })
+    }
+
+    /// The value of `param`, `None` when not set or not a parameter of this variant.
+    pub fn param(&self, param: AchainableParam) -> Option<&ParameterString> {
+        use AchainableParam::*;
+        match (self, param) {
+            (AchainableParams::AmountHolding(p), Amount) => Some(&p.amount),
+            (AchainableParams::AmountHolding(p), Date) => Some(&p.date),
+            (AchainableParams::AmountHolding(p), Token) => p.token.as_ref(),
+            (AchainableParams::AmountToken(p), Amount) => Some(&p.amount),
+            (AchainableParams::AmountToken(p), Token) => p.token.as_ref(),
+            (AchainableParams::Amount(p), Amount) => Some(&p.amount),
+            (AchainableParams::Amounts(p), Amount1) => Some(&p.amount1),
+            (AchainableParams::Amounts(p), Amount2) => Some(&p.amount2),
+            (AchainableParams::BetweenPercents(p), GreaterThanOrEqualTo) => {
+                Some(&p.greater_than_or_equal_to)
+            }
+            (AchainableParams::BetweenPercents(p), LessThanOrEqualTo) => {
+                Some(&p.less_than_or_equal_to)
+            }
+            (AchainableParams::ClassOfYear(p), Date1) => Some(&p.date1),
+            (AchainableParams::ClassOfYear(p), Date2) => Some(&p.date2),
+            (AchainableParams::DateInterval(p), StartDate) => Some(&p.start_date),
+            (AchainableParams::DateInterval(p), EndDate) => Some(&p.end_date),
+            (AchainableParams::DatePercent(p), Token) => Some(&p.token),
+            (AchainableParams::DatePercent(p), Date) => Some(&p.date),
+            (AchainableParams::DatePercent(p), Percent) => Some(&p.percent),
+            (AchainableParams::Date(p), Date) => Some(&p.date),
+            (AchainableParams::Token(p), Token) => Some(&p.token),
+            _ => None,
+        }
+    }
+}
+
+/// The `chain` parameter the worker expects for `network`, its name.
+pub fn achainable_chain(network: Web3Network) -> &'static str {
+    network.name()
+}
+
+/// The network of an Achainable `chain` parameter.
+pub fn achainable_network(chain: &str) -> Option<Web3Network> {
+    Web3Network::from_name(chain)
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum AchainableError {
+    #[error("unknown Achainable assertion {0:?}")]
+    UnknownName(String),
+    #[error("Achainable assertion {name:?} takes {expected:?} parameters, not {found:?}")]
+    WrongKind {
+        name: &'static str,
+        expected: AchainableKind,
+        found: AchainableKind,
+    },
+    #[error("unknown Achainable chain {0:?}")]
+    UnknownChain(String),
+    #[error("Achainable assertion {name:?} does not support {network:?}")]
+    UnsupportedNetwork {
+        name: &'static str,
+        network: Web3Network,
+    },
+    #[error("Achainable assertion {name:?} requires {param:?}")]
+    MissingParam {
+        name: &'static str,
+        param: AchainableParam,
+    },
+    #[error("Achainable assertion {name:?} does not take {param:?}")]
+    UnexpectedParam {
+        name: &'static str,
+        param: AchainableParam,
+    },
+}
+
+/**
+ * The Achainable assertions the worker knows, one entry per `name`.
+ *
+ * `DateInterval` has no entry: the worker builds "Account created between {dates}" from
+ * `ClassOfYear` parameters.
+ */
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct AchainableAssertion {
+    /// The `name` parameter, as the worker matches it
+    pub name: &'static str,
+    pub kind: AchainableKind,
+    /// Parameters that must be given besides `name` and `chain`
+    pub required: &'static [AchainableParam],
+    pub networks: &'static [Web3Network],
+}
+
+const COUNCIL_NETWORKS: &[Web3Network] = &[N::Litmus, N::Polkadot];
+const ACCOUNT_NETWORKS: &[Web3Network] = &[
+    N::Litentry,
+    N::Litmus,
+    N::Ethereum,
+    N::Polkadot,
+    N::Kusama,
+    N::Khala,
+    N::BSC,
+];
+const BALANCE_NETWORKS: &[Web3Network] = &[
+    N::Litentry,
+    N::Litmus,
+    N::Ethereum,
+    N::Polkadot,
+    N::Kusama,
+    N::Khala,
+];
+const ETHEREUM: &[Web3Network] = &[N::Ethereum];
+const BSC: &[Web3Network] = &[N::BSC];
+const ETHEREUM_AND_BSC: &[Web3Network] = &[N::Ethereum, N::BSC];
+
+const fn entry(
+    name: &'static str,
+    kind: AchainableKind,
+    required: &'static [AchainableParam],
+    networks: &'static [Web3Network],
+) -> AchainableAssertion {
+    AchainableAssertion {
+        name,
+        kind,
+        required,
+        networks,
+    }
+}
+
+const fn basic(name: &'static str, networks: &'static [Web3Network]) -> AchainableAssertion {
+    entry(name, AchainableKind::Basic, &[], networks)
+}
+
+#[rustfmt::skip]
+pub static ACHAINABLE_ASSERTIONS: [AchainableAssertion; 38] = [
+    basic("Validator", COUNCIL_NETWORKS),
+    basic("TreasuryProposalBeneficiary", COUNCIL_NETWORKS),
+    basic("TipFinder", COUNCIL_NETWORKS),
+    basic("TipBeneficiary", COUNCIL_NETWORKS),
+    basic("OpenGovProposer", COUNCIL_NETWORKS),
+    basic("FellowshipProposer", COUNCIL_NETWORKS),
+    basic("FellowshipMember", COUNCIL_NETWORKS),
+    basic("ExCouncilor", COUNCIL_NETWORKS),
+    basic("Councilor", COUNCIL_NETWORKS),
+    basic("BountyCurator", COUNCIL_NETWORKS),
+    entry("Balance between percents", AchainableKind::BetweenPercents, &[P::GreaterThanOrEqualTo, P::LessThanOrEqualTo], COUNCIL_NETWORKS),
+
+    basic("Account found on {chain}", ACCOUNT_NETWORKS),
+
+    entry("Account total transactions under {amount}", AchainableKind::Amount, &[P::Amount], BALANCE_NETWORKS),
+    entry("Balance under {amount}", AchainableKind::Amount, &[P::Amount], BALANCE_NETWORKS),
+    entry("Balance over {amount}", AchainableKind::Amount, &[P::Amount], BALANCE_NETWORKS),
+    entry("Balance over {amount} dollars", AchainableKind::Amount, &[P::Amount], BALANCE_NETWORKS),
+    entry("Balance between {amounts}", AchainableKind::Amounts, &[P::Amount1, P::Amount2], BALANCE_NETWORKS),
+    entry("Account created after {date}", AchainableKind::Date, &[P::Date], BALANCE_NETWORKS),
+    entry("Account created before {date}", AchainableKind::Date, &[P::Date], BALANCE_NETWORKS),
+    entry("Account created between {dates}", AchainableKind::ClassOfYear, &[P::Date1, P::Date2], BALANCE_NETWORKS),
+    entry("Balance hodling {amount} since {date}", AchainableKind::AmountHolding, &[P::Amount, P::Date], BALANCE_NETWORKS),
+
+    entry("ERC20 balance over {amount}", AchainableKind::AmountToken, &[P::Amount, P::Token], ETHEREUM),
+    basic("Uniswap V2 liquidity provider", ETHEREUM),
+    basic("Uniswap V3 liquidity provider", ETHEREUM),
+    basic("Curve Trader", ETHEREUM),
+    basic("Curve Liquidity Provider", ETHEREUM),
+    basic("MetaMask trader", ETHEREUM),
+    basic("Uniswap V2 trader", ETHEREUM),
+    basic("Uniswap V3 trader", ETHEREUM),
+    entry("Uniswap V2 {token} liquidity provider", AchainableKind::Token, &[P::Token], ETHEREUM),
+    entry("Uniswap V3 {token} liquidity provider", AchainableKind::Token, &[P::Token], ETHEREUM),
+    basic("Aave V2 Lender", ETHEREUM),
+    basic("Aave V2 Borrower", ETHEREUM),
+    basic("Aave V3 Lender", ETHEREUM),
+    basic("Aave V3 Borrower", ETHEREUM),
+    entry("ERC20 hodling {amount} of {token} since {date}", AchainableKind::AmountHolding, &[P::Amount, P::Token, P::Date], ETHEREUM),
+
+    entry("BEP20 balance over {amount}", AchainableKind::AmountToken, &[P::Amount, P::Token], BSC),
+
+    entry("Balance dropped {percent} since {date}", AchainableKind::DatePercent, &[P::Token, P::Date, P::Percent], ETHEREUM_AND_BSC),
+];
+
+impl AchainableAssertion {
+    pub fn find(name: &str) -> Option<&'static AchainableAssertion> {
+        ACHAINABLE_ASSERTIONS
+            .iter()
+            .find(|entry| entry.name == name)
+    }
+
+    /// The entry `params` were built from.
+    pub fn of(params: &AchainableParams) -> Option<&'static AchainableAssertion> {
+        std::str::from_utf8(&params.name())
+            .ok()
+            .and_then(Self::find)
+    }
+
+    /// Build the assertion for `network` from `params`, which must give every required
+    /// parameter and nothing the entry's variant doesn't take. The assertion is validated.
+    pub fn build(
+        &self,
+        network: Web3Network,
+        params: &[(AchainableParam, &str)],
+    ) -> Result<Assertion, AssertionError> {
+        if !self.networks.contains(&network) {
+            return Err(AchainableError::UnsupportedNetwork {
+                name: self.name,
+                network,
+            }
+            .into());
+        }
+        if let Some((param, _)) = params
+            .iter()
+            .find(|(param, _)| !self.kind.params().contains(param))
+        {
+            return Err(AchainableError::UnexpectedParam {
+                name: self.name,
+                param: *param,
+            }
+            .into());
+        }
+
+        let params = params
+            .iter()
+            .map(|(param, value)| Ok((*param, parameter_string(param.name(), value)?)))
+            .collect::<Result<Vec<_>, AssertionError>>()?;
+        let missing = |param| AchainableError::MissingParam {
+            name: self.name,
+            param,
+        };
+        if let Some(param) = self
+            .required
+            .iter()
+            .find(|required| !params.iter().any(|(param, _)| param == *required))
+        {
+            return Err(missing(*param).into());
+        }
+
+        let name = ParameterString::truncate_from(self.name.as_bytes().to_vec());
+        let chain = ParameterString::truncate_from(achainable_chain(network).as_bytes().to_vec());
+        let params = AchainableParams::new(self.kind, name, chain, &params).map_err(missing)?;
+        let assertion = Assertion::Achainable(params);
+        assertion.validate()?;
+        Ok(assertion)
+    }
+
+    /// Check `params` built by hand against the catalog, returning their entry.
+    pub fn check(
+        params: &AchainableParams,
+    ) -> Result<&'static AchainableAssertion, AchainableError> {
+        let name = params.name();
+        let entry = Self::of(params).ok_or_else(|| {
+            AchainableError::UnknownName(String::from_utf8_lossy(&name).into_owned())
+        })?;
+        if entry.kind != params.kind() {
+            return Err(AchainableError::WrongKind {
+                name: entry.name,
+                expected: entry.kind,
+                found: params.kind(),
+            });
+        }
+
+        let chain = params.chain();
+        let network = std::str::from_utf8(&chain)
+            .ok()
+            .and_then(achainable_network)
+            .ok_or_else(|| {
+                AchainableError::UnknownChain(String::from_utf8_lossy(&chain).into_owned())
+            })?;
+        if !entry.networks.contains(&network) {
+            return Err(AchainableError::UnsupportedNetwork {
+                name: entry.name,
+                network,
+            });
+        }
+
+        if let Some(param) = entry
+            .required
+            .iter()
+            .find(|param| params.param(**param).is_none())
+        {
+            return Err(AchainableError::MissingParam {
+                name: entry.name,
+                param: *param,
+            });
+        }
+
+        Ok(entry)
+    }
+}
+
+impl Assertion {
+    /// The Achainable assertion `name` for `network`, see `AchainableAssertion::build`.
+    pub fn achainable(
+        name: &str,
+        network: Web3Network,
+        params: &[(AchainableParam, &str)],
+    ) -> Result<Assertion, AssertionError> {
+        AchainableAssertion::find(name)
+            .ok_or_else(|| AchainableError::UnknownName(name.to_string()))?
+            .build(network, params)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn catalog_is_consistent() {
+        let names: HashSet<_> = ACHAINABLE_ASSERTIONS.iter().map(|e| e.name).collect();
+        assert_eq!(names.len(), ACHAINABLE_ASSERTIONS.len());
+
+        for entry in ACHAINABLE_ASSERTIONS.iter() {
+            assert!(entry.name.len() <= 64);
+            assert!(!entry.networks.is_empty());
+            assert!(entry
+                .required
+                .iter()
+                .all(|param| entry.kind.params().contains(param)));
+        }
+        assert!(Web3Network::iter().all(|n| achainable_network(achainable_chain(n)) == Some(n)));
+    }
+
+    #[test]
+    fn build_works() {
+        let assertion = Assertion::achainable(
+            "Account created between {dates}",
+            Web3Network::Ethereum,
+            &[(P::Date1, "2020-01-01"), (P::Date2, "2021-01-01")],
+        )
+        .unwrap();
+        let params = match &assertion {
+            Assertion::Achainable(params) => params,
+            _ => panic!("not an Achainable assertion"),
+        };
+        assert_eq!(params.kind(), AchainableKind::ClassOfYear);
+        assert_eq!(params.chain().to_vec(), b"ethereum".to_vec());
+        assert_eq!(
+            AchainableAssertion::check(params).map(|entry| entry.name),
+            Ok("Account created between {dates}")
+        );
+        assert_eq!(
+            assertion.get_supported_web3networks(),
+            Ok(BALANCE_NETWORKS.to_vec())
+        );
+
+        assert_eq!(
+            Assertion::achainable(
+                "Balance over {amount}",
+                Web3Network::BSC,
+                &[(P::Amount, "1")]
+            ),
+            Err(AchainableError::UnsupportedNetwork {
+                name: "Balance over {amount}",
+                network: Web3Network::BSC,
+            }
+            .into())
+        );
+        assert_eq!(
+            Assertion::achainable(
+                "ERC20 hodling {amount} of {token} since {date}",
+                Web3Network::Ethereum,
+                &[(P::Amount, "1"), (P::Date, "2020-01-01")],
+            ),
+            Err(AchainableError::MissingParam {
+                name: "ERC20 hodling {amount} of {token} since {date}",
+                param: P::Token,
+            }
+            .into())
+        );
+        assert_eq!(
+            Assertion::achainable("Validator", Web3Network::Polkadot, &[(P::Amount, "1")]),
+            Err(AchainableError::UnexpectedParam {
+                name: "Validator",
+                param: P::Amount,
+            }
+            .into())
+        );
+        assert_eq!(
+            Assertion::achainable("Balance hodling", Web3Network::Polkadot, &[]),
+            Err(AchainableError::UnknownName("Balance hodling".to_string()).into())
+        );
+    }
+
+    #[test]
+    fn check_rejects_mismatched_params() {
+        let params = AchainableParams::Amount(AchainableAmount {
+            name: ParameterString::truncate_from(b"Account created after {date}".to_vec()),
+            chain: ParameterString::truncate_from(b"ethereum".to_vec()),
+            amount: ParameterString::truncate_from(b"1".to_vec()),
+        });
+        assert_eq!(
+            AchainableAssertion::check(&params),
+            Err(AchainableError::WrongKind {
+                name: "Account created after {date}",
+                expected: AchainableKind::Date,
+                found: AchainableKind::Amount,
+            })
+        );
+        assert_eq!(
+            Assertion::Achainable(params).get_supported_web3networks(),
+            Ok(BALANCE_NETWORKS.to_vec())
+        );
+
+        let unknown = AchainableParams::Amount(AchainableAmount {
+            name: ParameterString::truncate_from(b"Balance hodling".to_vec()),
+            chain: ParameterString::truncate_from(b"ethereum".to_vec()),
+            amount: ParameterString::truncate_from(b"1".to_vec()),
+        });
+        assert_eq!(
+            Assertion::Achainable(unknown).get_supported_web3networks(),
+            Err(AchainableError::UnknownName("Balance hodling".to_string()))
+        );
+    }
+}
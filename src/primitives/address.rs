@@ -16,9 +16,12 @@
 
 use codec::{Decode, Encode, MaxEncodedLen};
 use scale_info::TypeInfo;
+#[cfg(feature = "std")]
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+#[cfg(feature = "std")]
+use std::str::FromStr;
 
 #[derive(Encode, Decode, Copy, Clone, Debug, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
-#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 pub struct Address20([u8; 20]);
 
 impl AsRef<[u8; 20]> for Address20 {
@@ -34,7 +37,6 @@ impl From<[u8; 20]> for Address20 {
 }
 
 #[derive(Encode, Decode, Copy, Clone, Debug, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
-#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 pub struct Address32([u8; 32]);
 impl AsRef<[u8; 32]> for Address32 {
     fn as_ref(&self) -> &[u8; 32] {
@@ -47,3 +49,89 @@ impl From<[u8; 32]> for Address32 {
         Self(value)
     }
 }
+
+/// Serialized as 0x-hex.
+#[cfg(feature = "std")]
+impl Serialize for Address20 {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&format!("0x{}", hex::encode(self.0)))
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'de> Deserialize<'de> for Address20 {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let address = String::deserialize(deserializer)?;
+        let bytes = hex::decode(address.trim_start_matches("0x"))
+            .map_err(|e| de::Error::custom(format!("{:?}", e)))?;
+        let bytes: [u8; 20] = bytes
+            .try_into()
+            .map_err(|_| de::Error::custom("address20 must be 20 bytes"))?;
+        Ok(Address20(bytes))
+    }
+}
+
+/// Serialized as 0x-hex, deserialized from 0x-hex or SS58 of any network.
+#[cfg(feature = "std")]
+impl Serialize for Address32 {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&format!("0x{}", hex::encode(self.0)))
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'de> Deserialize<'de> for Address32 {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let address = String::deserialize(deserializer)?;
+        let account =
+            sp_core::crypto::AccountId32::from_str(&address).map_err(de::Error::custom)?;
+        Ok(Address32(account.into()))
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn address_serde_works() {
+        let alice = Address32::from(alice_public());
+        let json = serde_json::to_string(&alice).unwrap();
+        assert_eq!(json, format!("\"0x{}\"", hex::encode(alice_public())));
+        assert_eq!(serde_json::from_str::<Address32>(&json).unwrap(), alice);
+        assert_eq!(
+            serde_json::from_str::<Address32>(
+                "\"5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY\""
+            )
+            .unwrap(),
+            alice
+        );
+        assert!(serde_json::from_str::<Address32>("\"0x1234\"").is_err());
+
+        let address20 = Address20::from([0x11u8; 20]);
+        let json = serde_json::to_string(&address20).unwrap();
+        assert_eq!(json, format!("\"0x{}\"", "11".repeat(20)));
+        assert_eq!(serde_json::from_str::<Address20>(&json).unwrap(), address20);
+        assert!(serde_json::from_str::<Address20>("\"0x11\"").is_err());
+    }
+
+    /// Alice's sr25519 public key
+    fn alice_public() -> [u8; 32] {
+        hex::decode("d43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d")
+            .unwrap()
+            .try_into()
+            .unwrap()
+    }
+}
@@ -21,6 +21,7 @@ use sp_runtime::BoundedVec;
 use crate::direct_call::types::AccountId;
 
 use super::{
+    achainable::{AchainableAssertion, AchainableError},
     network::{BoundedWeb3Network, Web3Network},
     MaxStringLength,
 };
@@ -175,9 +176,10 @@ impl Assertion {
     // - "identity networks" which are defined by the user and stored in `IdentityContext`
     //
     // returns a vector of `Web3Network` guarantees it's a subnet of
-    // the broader `Web3Network` (see network.rs)
-    pub fn get_supported_web3networks(&self) -> Vec<Web3Network> {
-        match self {
+    // the broader `Web3Network` (see network.rs), or an error for an `Achainable`
+    // assertion whose name is not in `ACHAINABLE_ASSERTIONS`
+    pub fn get_supported_web3networks(&self) -> Result<Vec<Web3Network>, AchainableError> {
+        let networks = match self {
             // LIT holder, not including `LitentryRococo` as it's not supported by any data provider
             Self::A4(..) => vec![
                 Web3Network::Litentry,
@@ -192,14 +194,16 @@ impl Assertion {
             Self::A8(network) => network.to_vec(),
             // polkadot paticipation
             Self::A14 => vec![Web3Network::Polkadot],
-            // Achainable Assertions
-            Self::Achainable(a) => {
-                let name = &a.name();
-                achainable_networks(name)
-            }
+            // Achainable Assertions, an unknown name supports no network at all
+            Self::Achainable(a) => AchainableAssertion::of(a)
+                .map(|entry| entry.networks.to_vec())
+                .ok_or_else(|| {
+                    AchainableError::UnknownName(String::from_utf8_lossy(&a.name()).into_owned())
+                })?,
             // we don't care about any specific web3 network
             _ => vec![],
-        }
+        };
+        Ok(networks)
     }
 }
 
@@ -219,74 +223,3 @@ pub const ASSERTION_FROM_DATE: [&str; 14] = [
     "2023-01-01",
     "2023-07-01",
 ];
-
-fn achainable_networks(name: &ParameterString) -> Vec<Web3Network> {
-    let name = &name.clone().to_vec();
-    let name = std::str::from_utf8(name).unwrap_or("");
-
-    if name == "Validator"
-        || name == "TreasuryProposalBeneficiary"
-        || name == "TipFinder"
-        || name == "TipBeneficiary"
-        || name == "OpenGovProposer"
-        || name == "FellowshipProposer"
-        || name == "FellowshipMember"
-        || name == "ExCouncilor"
-        || name == "Councilor"
-        || name == "BountyCurator"
-        || name == "Balance between percents"
-    {
-        return vec![Web3Network::Litmus, Web3Network::Polkadot];
-    } else if name == "Account found on {chain}" {
-        return vec![
-            Web3Network::Litentry,
-            Web3Network::Litmus,
-            Web3Network::Ethereum,
-            Web3Network::Polkadot,
-            Web3Network::Kusama,
-            Web3Network::Khala,
-            Web3Network::BSC,
-        ];
-    } else if name == "Account total transactions under {amount}"
-        || name == "Balance under {amount}"
-        || name == "Balance over {amount}"
-        || name == "Balance over {amount} dollars"
-        || name == "Balance between {amounts}"
-        || name == "Account created after {date}"
-        || name == "Account created before {date}"
-        || name == "Account created between {dates}"
-        || name == "Balance hodling {amount} since {date}"
-    {
-        return vec![
-            Web3Network::Litentry,
-            Web3Network::Litmus,
-            Web3Network::Ethereum,
-            Web3Network::Polkadot,
-            Web3Network::Kusama,
-            Web3Network::Khala,
-        ];
-    } else if name == "ERC20 balance over {amount}"
-        || name == "Uniswap V2 liquidity provider"
-        || name == "Uniswap V3 liquidity provider"
-        || name == "Curve Trader"
-        || name == "Curve Liquidity Provider"
-        || name == "MetaMask trader"
-        || name == "Uniswap V2 trader"
-        || name == "Uniswap V3 trader"
-        || name == "Uniswap V2 {token} liquidity provider"
-        || name == "Uniswap V3 {token} liquidity provider"
-        || name == "Aave V2 Lender"
-        || name == "Aave V2 Borrower"
-        || name == "Aave V3 Lender"
-        || name == "Aave V3 Borrower"
-        || name == "ERC20 hodling {amount} of {token} since {date}"
-    {
-        return vec![Web3Network::Ethereum];
-    } else if name == "BEP20 balance over {amount}" {
-        return vec![Web3Network::BSC];
-    } else if name == "Balance dropped {percent} since {date}" {
-        return vec![Web3Network::Ethereum, Web3Network::BSC];
-    }
-
-    vec![]
-}
@@ -0,0 +1,390 @@
+// Copyright 2020-2023 Litentry Technologies GmbH.
+// This file is part of Litentry.
+//
+// Litentry is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Litentry is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
+
+use super::{
+    achainable::{AchainableAssertion, AchainableError, AchainableKind, AchainableParam},
+    assertion::Assertion,
+    network::Web3Network,
+};
+
+/// Who may request an assertion.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Requester {
+    /// Any user, about their own linked identities
+    Anyone,
+    /// Only a delegatee, about the account given as parameter
+    Delegatee,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ParamType {
+    Text,
+    /// Non-negative decimal number
+    Amount,
+    /// `YYYY-MM-DD`
+    Date,
+    /// Decimal number between 0 and 100
+    Percent,
+    /// `Web3Network`s
+    Networks,
+    AccountId,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ParamInfo {
+    pub name: &'static str,
+    pub param_type: ParamType,
+    pub optional: bool,
+}
+
+const fn param(name: &'static str, param_type: ParamType) -> ParamInfo {
+    ParamInfo {
+        name,
+        param_type,
+        optional: false,
+    }
+}
+
+/// What an assertion type is about, one per `Assertion` variant.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct AssertionInfo {
+    /// The variant name, as in the textual form of assertions
+    pub id: &'static str,
+    pub description: &'static str,
+    /// Parameters of the variant, in order. Those of `Achainable` depend on its kind, see
+    /// `AssertionMetadata`.
+    pub params: &'static [ParamInfo],
+    pub requester: Requester,
+    /// `credentialSubject.type` of the issued credentials, `None` while no sample documents it.
+    /// `vc_management::schema` pins its schemas to these.
+    pub expected_subject_type: Option<&'static str>,
+}
+
+const MINIMUM_AMOUNT: &[ParamInfo] = &[param("minimum_amount", ParamType::Amount)];
+
+pub const A1_INFO: AssertionInfo = AssertionInfo {
+    id: "A1",
+    description: "The user has verified one identity in Web 2 and one identity in Web 3",
+    params: &[],
+    requester: Requester::Anyone,
+    expected_subject_type: Some("Basic Identity Verification"),
+};
+
+pub const A2_INFO: AssertionInfo = AssertionInfo {
+    id: "A2",
+    description: "The user has obtained an ID-Hubber role in a Litentry Discord channel",
+    params: &[param("guild_id", ParamType::Text)],
+    requester: Requester::Anyone,
+    expected_subject_type: Some("Discord ID-Hubber Role Verification"),
+};
+
+pub const A3_INFO: AssertionInfo = AssertionInfo {
+    id: "A3",
+    description: "The user has commented in a specific Discord channel with a specific role",
+    params: &[
+        param("guild_id", ParamType::Text),
+        param("channel_id", ParamType::Text),
+        param("role_id", ParamType::Text),
+    ],
+    requester: Requester::Anyone,
+    expected_subject_type: Some("Discord Member Verification"),
+};
+
+pub const A4_INFO: AssertionInfo = AssertionInfo {
+    id: "A4",
+    description: "Since when has the user been consistently holding a min amount of LIT token",
+    params: MINIMUM_AMOUNT,
+    requester: Requester::Anyone,
+    expected_subject_type: Some("LIT Holding Assertion"),
+};
+
+pub const A6_INFO: AssertionInfo = AssertionInfo {
+    id: "A6",
+    description: "The range of the user's Twitter follower count",
+    params: &[],
+    requester: Requester::Anyone,
+    expected_subject_type: Some("Twitter Follower Amount"),
+};
+
+pub const A7_INFO: AssertionInfo = AssertionInfo {
+    id: "A7",
+    description: "Since when has the user been consistently holding a min amount of DOT token",
+    params: MINIMUM_AMOUNT,
+    requester: Requester::Anyone,
+    expected_subject_type: Some("DOT Holding Assertion"),
+};
+
+pub const A8_INFO: AssertionInfo = AssertionInfo {
+    id: "A8",
+    description:
+        "The total amount of transactions the user has ever made in each of the given networks",
+    params: &[param("networks", ParamType::Networks)],
+    requester: Requester::Anyone,
+    expected_subject_type: Some("EVM/Substrate Transaction Count on Networks"),
+};
+
+pub const A9_INFO: AssertionInfo = AssertionInfo {
+    id: "A9",
+    // TODO: describe what A9 asserts, nothing in this repository documents it yet
+    description: "Undocumented: the SDK does not know yet what A9 asserts",
+    params: &[],
+    requester: Requester::Anyone,
+    expected_subject_type: None,
+};
+
+pub const A10_INFO: AssertionInfo = AssertionInfo {
+    id: "A10",
+    description: "Since when has the user been consistently holding a min amount of WBTC token",
+    params: MINIMUM_AMOUNT,
+    requester: Requester::Anyone,
+    expected_subject_type: Some("WBTC Holding Assertion"),
+};
+
+pub const A11_INFO: AssertionInfo = AssertionInfo {
+    id: "A11",
+    description: "Since when has the user been consistently holding a min amount of ETH token",
+    params: MINIMUM_AMOUNT,
+    requester: Requester::Anyone,
+    expected_subject_type: Some("ETH Holding Assertion"),
+};
+
+pub const A13_INFO: AssertionInfo = AssertionInfo {
+    id: "A13",
+    description: "The account participated in Polkadot Decoded 2023",
+    params: &[param("participant_account", ParamType::AccountId)],
+    requester: Requester::Delegatee,
+    expected_subject_type: None,
+};
+
+pub const A14_INFO: AssertionInfo = AssertionInfo {
+    id: "A14",
+    description: "The user's participation in Polkadot, checked on their Polkadot identities, \
+                  for Polkadot Decoded 2023",
+    params: &[],
+    requester: Requester::Anyone,
+    expected_subject_type: None,
+};
+
+pub const ACHAINABLE_INFO: AssertionInfo = AssertionInfo {
+    id: "Achainable",
+    description:
+        "On-chain activity of the user computed by Achainable, see `ACHAINABLE_ASSERTIONS`",
+    params: &[
+        param("name", ParamType::Text),
+        param("chain", ParamType::Text),
+    ],
+    requester: Requester::Anyone,
+    expected_subject_type: None,
+};
+
+/// The info of every `Assertion` variant, in declaration order.
+pub static ASSERTION_INFOS: [&AssertionInfo; 13] = [
+    &A1_INFO,
+    &A2_INFO,
+    &A3_INFO,
+    &A4_INFO,
+    &A6_INFO,
+    &A7_INFO,
+    &A8_INFO,
+    &A9_INFO,
+    &A10_INFO,
+    &A11_INFO,
+    &A13_INFO,
+    &A14_INFO,
+    &ACHAINABLE_INFO,
+];
+
+impl AssertionInfo {
+    pub fn of(assertion: &Assertion) -> &'static AssertionInfo {
+        match assertion {
+            Assertion::A1 => &A1_INFO,
+            Assertion::A2(..) => &A2_INFO,
+            Assertion::A3(..) => &A3_INFO,
+            Assertion::A4(..) => &A4_INFO,
+            Assertion::A6 => &A6_INFO,
+            Assertion::A7(..) => &A7_INFO,
+            Assertion::A8(..) => &A8_INFO,
+            Assertion::A9 => &A9_INFO,
+            Assertion::A10(..) => &A10_INFO,
+            Assertion::A11(..) => &A11_INFO,
+            Assertion::A13(..) => &A13_INFO,
+            Assertion::A14 => &A14_INFO,
+            Assertion::Achainable(..) => &ACHAINABLE_INFO,
+        }
+    }
+
+    pub fn find(id: &str) -> Option<&'static AssertionInfo> {
+        ASSERTION_INFOS.into_iter().find(|info| info.id == id)
+    }
+}
+
+/// Everything known about an assertion with its parameters.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AssertionMetadata {
+    pub info: &'static AssertionInfo,
+    /// `info.params`, followed by those of the kind for `Achainable`
+    pub params: Vec<ParamInfo>,
+    pub networks: Vec<Web3Network>,
+}
+
+impl Assertion {
+    pub fn info(&self) -> &'static AssertionInfo {
+        AssertionInfo::of(self)
+    }
+
+    /// Fails for an `Achainable` assertion of an unknown name.
+    pub fn metadata(&self) -> Result<AssertionMetadata, AchainableError> {
+        let info = self.info();
+        let mut params = info.params.to_vec();
+        if let Assertion::Achainable(achainable) = self {
+            params.extend(achainable_params(
+                achainable.kind(),
+                AchainableAssertion::of(achainable),
+            ));
+        }
+
+        Ok(AssertionMetadata {
+            info,
+            params,
+            networks: self.get_supported_web3networks()?,
+        })
+    }
+}
+
+/// The parameters of `kind`, those `entry` doesn't require being optional. Without an entry
+/// only the `token` of the kinds taking an `Option` is.
+fn achainable_params(
+    kind: AchainableKind,
+    entry: Option<&AchainableAssertion>,
+) -> impl Iterator<Item = ParamInfo> + '_ {
+    kind.params().iter().map(move |achainable_param| {
+        let param_type = match achainable_param {
+            AchainableParam::Amount | AchainableParam::Amount1 | AchainableParam::Amount2 => {
+                ParamType::Amount
+            }
+            AchainableParam::Date
+            | AchainableParam::Date1
+            | AchainableParam::Date2
+            | AchainableParam::StartDate
+            | AchainableParam::EndDate => ParamType::Date,
+            AchainableParam::Percent
+            | AchainableParam::GreaterThanOrEqualTo
+            | AchainableParam::LessThanOrEqualTo => ParamType::Percent,
+            AchainableParam::Token => ParamType::Text,
+        };
+        let optional = match entry {
+            Some(entry) => !entry.required.contains(achainable_param),
+            None => {
+                *achainable_param == AchainableParam::Token
+                    && matches!(
+                        kind,
+                        AchainableKind::AmountHolding | AchainableKind::AmountToken
+                    )
+            }
+        };
+
+        ParamInfo {
+            name: achainable_param.name(),
+            param_type,
+            optional,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        primitives::assertion::ParameterString, vc_management::schema::schema_for_assertion,
+    };
+    use sp_core::crypto::AccountId32;
+
+    #[test]
+    fn info_of_works() {
+        let amount = ParameterString::truncate_from(b"1".to_vec());
+        let assertions = [
+            Assertion::A1,
+            Assertion::A2(amount.clone()),
+            Assertion::A3(amount.clone(), amount.clone(), amount.clone()),
+            Assertion::A4(amount.clone()),
+            Assertion::A6,
+            Assertion::A7(amount.clone()),
+            Assertion::A8(Default::default()),
+            Assertion::A9,
+            Assertion::A10(amount.clone()),
+            Assertion::A11(amount.clone()),
+            Assertion::A13(AccountId32::new([0u8; 32])),
+            Assertion::A14,
+            Assertion::achainable("Validator", Web3Network::Polkadot, &[]).unwrap(),
+        ];
+
+        for (assertion, info) in assertions.iter().zip(ASSERTION_INFOS) {
+            assert_eq!(assertion.info(), info);
+            assert_eq!(AssertionInfo::find(info.id), Some(info));
+            assert_eq!(
+                schema_for_assertion(assertion).subject_type,
+                info.expected_subject_type
+            );
+            // the textual form starts with the id
+            assert!(assertion
+                .to_string()
+                .to_lowercase()
+                .starts_with(&info.id.to_lowercase()));
+        }
+        assert_eq!(assertions[10].info().requester, Requester::Delegatee);
+    }
+
+    #[test]
+    fn metadata_works() {
+        let a4 = Assertion::a4("10").unwrap().metadata().unwrap();
+        assert_eq!(a4.params, MINIMUM_AMOUNT.to_vec());
+        assert_eq!(
+            a4.networks,
+            vec![
+                Web3Network::Litentry,
+                Web3Network::Litmus,
+                Web3Network::Ethereum
+            ]
+        );
+
+        let holding = Assertion::achainable(
+            "Balance hodling {amount} since {date}",
+            Web3Network::Litmus,
+            &[
+                (AchainableParam::Amount, "10"),
+                (AchainableParam::Date, "2017-01-01"),
+            ],
+        )
+        .unwrap()
+        .metadata()
+        .unwrap();
+        assert_eq!(
+            holding.params,
+            vec![
+                param("name", ParamType::Text),
+                param("chain", ParamType::Text),
+                param("amount", ParamType::Amount),
+                param("date", ParamType::Date),
+                ParamInfo {
+                    name: "token",
+                    param_type: ParamType::Text,
+                    optional: true,
+                },
+            ]
+        );
+        assert!(holding.networks.contains(&Web3Network::Litmus));
+    }
+}
@@ -132,3 +132,26 @@ pub enum TrustedOperationStatus {
     /// TrustedOperation is no longer valid in the current state.
     Invalid,
 }
+
+impl TrustedOperationStatus {
+    /// No further status updates follow a final one.
+    pub fn is_final(&self) -> bool {
+        matches!(
+            self,
+            TrustedOperationStatus::InSidechainBlock(_)
+                | TrustedOperationStatus::Finalized
+                | TrustedOperationStatus::FinalityTimeout
+                | TrustedOperationStatus::Usurped
+                | TrustedOperationStatus::Dropped
+                | TrustedOperationStatus::Invalid
+        )
+    }
+
+    /// The operation was executed in a sidechain block.
+    pub fn is_success(&self) -> bool {
+        matches!(
+            self,
+            TrustedOperationStatus::InSidechainBlock(_) | TrustedOperationStatus::Finalized
+        )
+    }
+}
@@ -0,0 +1,608 @@
+// Copyright 2020-2023 Litentry Technologies GmbH.
+// This file is part of Litentry.
+//
+// Litentry is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Litentry is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
+
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::{fmt, str::FromStr};
+use thiserror::Error;
+
+use super::{
+    achainable::{AchainableKind, AchainableParam},
+    assertion::{AchainableParams, Assertion, ParameterString},
+    network::{BoundedWeb3Network, Web3Network, MAX_WEB3NETWORK_LEN},
+    params::{parameter_string, AssertionError},
+    AccountId,
+};
+
+/**
+ * Compact textual form of assertions, also their serde representation:
+ *
+ * A1, A4(10), A3(guild,channel,role), A8[polkadot,kusama], A13(<0x-hex account>),
+ * achainable:ClassOfYear{name="Account created between {dates}",chain=ethereum,date1=...}
+ *
+ * Parameters containing blanks, separators or quotes are quoted, `\` escapes `"` and `\` in
+ * quoted parameters. Accounts are written in 0x-hex like `Address32`, parsing also takes SS58.
+ * Parsing only checks the structure and that parameters are neither empty nor too long, see
+ * `Assertion::validate`.
+ */
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ParseAssertionError {
+    #[error("expected {expected} at {position}")]
+    Expected {
+        expected: &'static str,
+        position: usize,
+    },
+    #[error("unknown assertion {0}")]
+    UnknownAssertion(String),
+    #[error("{assertion} takes {expected} parameters, not {found}")]
+    Arity {
+        assertion: String,
+        expected: usize,
+        found: usize,
+    },
+    #[error("unknown network {0}")]
+    UnknownNetwork(String),
+    #[error("more than {0} networks")]
+    TooManyNetworks(u32),
+    #[error("invalid account id {0}")]
+    InvalidAccountId(String),
+    #[error("unknown Achainable kind {0}")]
+    UnknownKind(String),
+    #[error("{kind:?} takes no parameter {param}")]
+    UnknownParam { kind: AchainableKind, param: String },
+    #[error("parameter {0} is given twice")]
+    DuplicateParam(String),
+    #[error("missing parameter {0}")]
+    MissingParam(&'static str),
+    #[error(transparent)]
+    Param(#[from] AssertionError),
+}
+
+const ACHAINABLE_PREFIX: &str = "achainable:";
+const SPECIAL_CHARS: &str = ",()[]{}=\"\\";
+
+/// A parameter as written, quoted when needed.
+struct Param<'a>(&'a [u8]);
+
+impl fmt::Display for Param<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let value = String::from_utf8_lossy(self.0);
+        let bare = !value.is_empty()
+            && !value
+                .chars()
+                .any(|c| c.is_whitespace() || SPECIAL_CHARS.contains(c));
+        if bare {
+            return f.write_str(&value);
+        }
+
+        f.write_str("\"")?;
+        for c in value.chars() {
+            if c == '"' || c == '\\' {
+                f.write_str("\\")?;
+            }
+            write!(f, "{}", c)?;
+        }
+        f.write_str("\"")
+    }
+}
+
+impl fmt::Display for Assertion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Assertion::A1 => f.write_str("A1"),
+            Assertion::A2(guild_id) => write!(f, "A2({})", Param(guild_id)),
+            Assertion::A3(guild_id, channel_id, role_id) => write!(
+                f,
+                "A3({},{},{})",
+                Param(guild_id),
+                Param(channel_id),
+                Param(role_id)
+            ),
+            Assertion::A4(minimum_amount) => write!(f, "A4({})", Param(minimum_amount)),
+            Assertion::A6 => f.write_str("A6"),
+            Assertion::A7(minimum_amount) => write!(f, "A7({})", Param(minimum_amount)),
+            Assertion::A8(networks) => {
+                let networks: Vec<_> = networks.iter().map(Web3Network::name).collect();
+                write!(f, "A8[{}]", networks.join(","))
+            }
+            Assertion::A9 => f.write_str("A9"),
+            Assertion::A10(minimum_amount) => write!(f, "A10({})", Param(minimum_amount)),
+            Assertion::A11(minimum_amount) => write!(f, "A11({})", Param(minimum_amount)),
+            Assertion::A13(account) => write!(f, "A13(0x{})", hex::encode(account)),
+            Assertion::A14 => f.write_str("A14"),
+            Assertion::Achainable(params) => {
+                let kind = params.kind();
+                write!(
+                    f,
+                    "{}{}{{name={},chain={}",
+                    ACHAINABLE_PREFIX,
+                    kind.name(),
+                    Param(&params.name()),
+                    Param(&params.chain())
+                )?;
+                for param in kind.params() {
+                    if let Some(value) = params.param(*param) {
+                        write!(f, ",{}={}", param.name(), Param(value))?;
+                    }
+                }
+                f.write_str("}")
+            }
+        }
+    }
+}
+
+impl FromStr for Assertion {
+    type Err = ParseAssertionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser {
+            input: s,
+            position: 0,
+        };
+        let assertion = parser.assertion()?;
+        parser.end()?;
+        Ok(assertion)
+    }
+}
+
+impl Serialize for Assertion {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for Assertion {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(de::Error::custom)
+    }
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn assertion(&mut self) -> Result<Assertion, ParseAssertionError> {
+        self.skip_whitespace();
+        if self.rest().starts_with(ACHAINABLE_PREFIX) {
+            self.position += ACHAINABLE_PREFIX.len();
+            return self.achainable();
+        }
+
+        let name = self.ident()?;
+        Ok(match name {
+            "A1" => Assertion::A1,
+            "A2" => Assertion::A2(self.param(name, "guild_id")?),
+            "A3" => {
+                let mut values = self
+                    .params(name, &["guild_id", "channel_id", "role_id"])?
+                    .into_iter();
+                // the arity was checked
+                let mut next = || values.next().unwrap_or_default();
+                Assertion::A3(next(), next(), next())
+            }
+            "A4" => Assertion::A4(self.param(name, "minimum_amount")?),
+            "A6" => Assertion::A6,
+            "A7" => Assertion::A7(self.param(name, "minimum_amount")?),
+            "A8" => Assertion::A8(self.networks()?),
+            "A9" => Assertion::A9,
+            "A10" => Assertion::A10(self.param(name, "minimum_amount")?),
+            "A11" => Assertion::A11(self.param(name, "minimum_amount")?),
+            "A13" => {
+                let values = self.args(name, 1)?;
+                let account = AccountId::from_str(&values[0])
+                    .map_err(|_| ParseAssertionError::InvalidAccountId(values[0].clone()))?;
+                Assertion::A13(account)
+            }
+            "A14" => Assertion::A14,
+            _ => return Err(ParseAssertionError::UnknownAssertion(name.to_string())),
+        })
+    }
+
+    /// The `arity` parameters of `assertion`, in parentheses.
+    fn args(&mut self, assertion: &str, arity: usize) -> Result<Vec<String>, ParseAssertionError> {
+        let values = self.list('(', ')')?;
+        if values.len() != arity {
+            return Err(ParseAssertionError::Arity {
+                assertion: assertion.to_string(),
+                expected: arity,
+                found: values.len(),
+            });
+        }
+        Ok(values)
+    }
+
+    fn params(
+        &mut self,
+        assertion: &str,
+        params: &[&'static str],
+    ) -> Result<Vec<ParameterString>, ParseAssertionError> {
+        self.args(assertion, params.len())?
+            .into_iter()
+            .zip(params)
+            .map(|(value, param)| parameter_string(*param, &value).map_err(Into::into))
+            .collect()
+    }
+
+    fn param(
+        &mut self,
+        assertion: &str,
+        param: &'static str,
+    ) -> Result<ParameterString, ParseAssertionError> {
+        self.params(assertion, &[param])
+            .map(|mut values| values.remove(0))
+    }
+
+    fn networks(&mut self) -> Result<BoundedWeb3Network, ParseAssertionError> {
+        let networks = self
+            .list('[', ']')?
+            .iter()
+            .map(|name| {
+                Web3Network::from_name(name)
+                    .ok_or_else(|| ParseAssertionError::UnknownNetwork(name.clone()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        BoundedWeb3Network::try_from(networks)
+            .map_err(|_| ParseAssertionError::TooManyNetworks(MAX_WEB3NETWORK_LEN))
+    }
+
+    fn achainable(&mut self) -> Result<Assertion, ParseAssertionError> {
+        let kind_name = self.ident()?;
+        let kind = AchainableKind::from_name(kind_name)
+            .ok_or_else(|| ParseAssertionError::UnknownKind(kind_name.to_string()))?;
+
+        let (mut name, mut chain, mut params) = (None, None, vec![]);
+        self.expect('{')?;
+        self.skip_whitespace();
+        if !self.eat('}') {
+            loop {
+                let key = self.ident()?;
+                self.expect('=')?;
+                let value = self.value()?;
+                match key {
+                    "name" | "chain" => {
+                        let (slot, param) = if key == "name" {
+                            (&mut name, "name")
+                        } else {
+                            (&mut chain, "chain")
+                        };
+                        if slot.is_some() {
+                            return Err(ParseAssertionError::DuplicateParam(key.to_string()));
+                        }
+                        *slot = Some(parameter_string(param, &value)?);
+                    }
+                    _ => {
+                        let param = AchainableParam::from_name(key)
+                            .filter(|param| kind.params().contains(param))
+                            .ok_or_else(|| ParseAssertionError::UnknownParam {
+                                kind,
+                                param: key.to_string(),
+                            })?;
+                        if params.iter().any(|(p, _)| *p == param) {
+                            return Err(ParseAssertionError::DuplicateParam(key.to_string()));
+                        }
+                        params.push((param, parameter_string(param.name(), &value)?));
+                    }
+                }
+                if self.separator('}')? {
+                    break;
+                }
+            }
+        }
+
+        let name = name.ok_or(ParseAssertionError::MissingParam("name"))?;
+        let chain = chain.ok_or(ParseAssertionError::MissingParam("chain"))?;
+        AchainableParams::new(kind, name, chain, &params)
+            .map(Assertion::Achainable)
+            .map_err(|param| ParseAssertionError::MissingParam(param.name()))
+    }
+
+    /// Comma separated parameters between `open` and `close`.
+    fn list(&mut self, open: char, close: char) -> Result<Vec<String>, ParseAssertionError> {
+        self.expect(open)?;
+        let mut values = vec![];
+        self.skip_whitespace();
+        if self.eat(close) {
+            return Ok(values);
+        }
+        loop {
+            values.push(self.value()?);
+            if self.separator(close)? {
+                return Ok(values);
+            }
+        }
+    }
+
+    /// Whether `close` ends the list, after a `,` it continues.
+    fn separator(&mut self, close: char) -> Result<bool, ParseAssertionError> {
+        self.skip_whitespace();
+        if self.eat(close) {
+            Ok(true)
+        } else if self.eat(',') {
+            Ok(false)
+        } else {
+            Err(self.expected("`,` or a closing bracket"))
+        }
+    }
+
+    fn value(&mut self) -> Result<String, ParseAssertionError> {
+        self.skip_whitespace();
+        if !self.eat('"') {
+            let value: String = self
+                .rest()
+                .chars()
+                .take_while(|c| !c.is_whitespace() && !SPECIAL_CHARS.contains(*c))
+                .collect();
+            if value.is_empty() {
+                return Err(self.expected("a parameter"));
+            }
+            self.position += value.len();
+            return Ok(value);
+        }
+
+        let mut value = String::new();
+        let mut chars = self.rest().chars();
+        loop {
+            let c = chars.next().ok_or_else(|| self.expected("`\"`"))?;
+            self.position += c.len_utf8();
+            match c {
+                '"' => return Ok(value),
+                '\\' => {
+                    let escaped = chars.next().ok_or_else(|| self.expected("`\"`"))?;
+                    self.position += escaped.len_utf8();
+                    value.push(escaped);
+                }
+                _ => value.push(c),
+            }
+        }
+    }
+
+    fn ident(&mut self) -> Result<&'a str, ParseAssertionError> {
+        self.skip_whitespace();
+        let rest = self.rest();
+        let len = rest
+            .find(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+            .unwrap_or(rest.len());
+        if len == 0 {
+            return Err(self.expected("a name"));
+        }
+        self.position += len;
+        Ok(&rest[..len])
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), ParseAssertionError> {
+        self.skip_whitespace();
+        if !self.eat(c) {
+            return Err(self.expected(match c {
+                '(' => "`(`",
+                '[' => "`[`",
+                '{' => "`{`",
+                '=' => "`=`",
+                _ => "a separator",
+            }));
+        }
+        Ok(())
+    }
+
+    fn end(&mut self) -> Result<(), ParseAssertionError> {
+        self.skip_whitespace();
+        if !self.rest().is_empty() {
+            return Err(self.expected("the end"));
+        }
+        Ok(())
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.rest().starts_with(c) {
+            self.position += c.len_utf8();
+            return true;
+        }
+        false
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.position += rest.len() - rest.trim_start().len();
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.input[self.position..]
+    }
+
+    fn expected(&self, expected: &'static str) -> ParseAssertionError {
+        ParseAssertionError::Expected {
+            expected,
+            position: self.position,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sp_core::crypto::Ss58Codec;
+    use strum::IntoEnumIterator;
+
+    fn string(value: &str) -> ParameterString {
+        ParameterString::truncate_from(value.as_bytes().to_vec())
+    }
+
+    fn all_assertions() -> Vec<Assertion> {
+        let mut assertions = vec![
+            Assertion::A1,
+            Assertion::A2(string("1034083718425493544")),
+            Assertion::A3(string("guild"), string("chan nel"), string("r\"o\\le")),
+            Assertion::A4(string("10")),
+            Assertion::A6,
+            Assertion::A7(string("0.5")),
+            Assertion::A8(
+                BoundedWeb3Network::try_from(vec![Web3Network::Polkadot, Web3Network::BSC])
+                    .unwrap(),
+            ),
+            Assertion::A8(BoundedWeb3Network::default()),
+            Assertion::A9,
+            Assertion::A10(string("0")),
+            Assertion::A11(string("1")),
+            Assertion::A13(AccountId::new([7u8; 32])),
+            Assertion::A14,
+        ];
+        for kind in AchainableKind::iter() {
+            let params: Vec<_> = kind
+                .params()
+                .iter()
+                .map(|param| (*param, string(param.name())))
+                .collect();
+            let params =
+                AchainableParams::new(kind, string("Balance {x}, y"), string("ethereum"), &params)
+                    .unwrap();
+            assertions.push(Assertion::Achainable(params));
+        }
+        // without the optional token
+        assertions.push(Assertion::Achainable(
+            AchainableParams::new(
+                AchainableKind::AmountHolding,
+                string("Balance hodling {amount} since {date}"),
+                string("litentry"),
+                &[
+                    (AchainableParam::Amount, string("10")),
+                    (AchainableParam::Date, string("2017-01-01")),
+                ],
+            )
+            .unwrap(),
+        ));
+        assertions
+    }
+
+    #[test]
+    fn round_trip_works() {
+        for assertion in all_assertions() {
+            let text = assertion.to_string();
+            assert_eq!(text.parse::<Assertion>(), Ok(assertion.clone()), "{}", text);
+
+            let json = serde_json::to_string(&assertion).unwrap();
+            assert_eq!(serde_json::from_str::<Assertion>(&json).unwrap(), assertion);
+        }
+    }
+
+    #[test]
+    fn textual_form_works() {
+        assert_eq!(Assertion::A4(string("10")).to_string(), "A4(10)");
+        assert_eq!(
+            " A8[ polkadot , kusama ] ".parse::<Assertion>(),
+            Ok(Assertion::A8(
+                BoundedWeb3Network::try_from(vec![Web3Network::Polkadot, Web3Network::Kusama])
+                    .unwrap()
+            ))
+        );
+
+        let class_of_year = Assertion::Achainable(AchainableParams::ClassOfYear(
+            crate::primitives::assertion::AchainableClassOfYear {
+                name: string("Account created between {dates}"),
+                chain: string("ethereum"),
+                date1: string("2020-01-01"),
+                date2: string("2021-01-01"),
+            },
+        ));
+        let text = "achainable:ClassOfYear{name=\"Account created between {dates}\",chain=ethereum,date1=2020-01-01,date2=2021-01-01}";
+        assert_eq!(class_of_year.to_string(), text);
+        assert_eq!(text.parse::<Assertion>(), Ok(class_of_year));
+
+        let account = AccountId::new([7u8; 32]);
+        let text = format!("A13(0x{})", hex::encode([7u8; 32]));
+        assert_eq!(Assertion::A13(account.clone()).to_string(), text);
+        assert_eq!(
+            text.parse::<Assertion>(),
+            Ok(Assertion::A13(account.clone()))
+        );
+        assert_eq!(
+            format!("A13({})", account.to_ss58check()).parse::<Assertion>(),
+            Ok(Assertion::A13(account))
+        );
+    }
+
+    #[test]
+    fn parse_errors_work() {
+        assert_eq!(
+            "A5".parse::<Assertion>(),
+            Err(ParseAssertionError::UnknownAssertion("A5".to_string()))
+        );
+        assert_eq!(
+            "A3(1,2)".parse::<Assertion>(),
+            Err(ParseAssertionError::Arity {
+                assertion: "A3".to_string(),
+                expected: 3,
+                found: 2,
+            })
+        );
+        assert_eq!(
+            "A8[polkadot,solana]".parse::<Assertion>(),
+            Err(ParseAssertionError::UnknownNetwork("solana".to_string()))
+        );
+        assert_eq!(
+            "A4(10".parse::<Assertion>(),
+            Err(ParseAssertionError::Expected {
+                expected: "`,` or a closing bracket",
+                position: 5,
+            })
+        );
+        assert_eq!(
+            "A1 A6".parse::<Assertion>(),
+            Err(ParseAssertionError::Expected {
+                expected: "the end",
+                position: 3,
+            })
+        );
+        assert_eq!(
+            "achainable:Date{name=x,chain=bsc}".parse::<Assertion>(),
+            Err(ParseAssertionError::MissingParam("date"))
+        );
+        assert_eq!(
+            "achainable:Date{name=x,chain=bsc,date=1,amount=2}".parse::<Assertion>(),
+            Err(ParseAssertionError::UnknownParam {
+                kind: AchainableKind::Date,
+                param: "amount".to_string(),
+            })
+        );
+        assert_eq!(
+            "A13(5Grw)".parse::<Assertion>(),
+            Err(ParseAssertionError::InvalidAccountId("5Grw".to_string()))
+        );
+        assert_eq!(
+            "A10(\"\")".parse::<Assertion>(),
+            Err(AssertionError::Empty {
+                param: "minimum_amount"
+            }
+            .into())
+        );
+        assert_eq!(
+            format!("A2({})", "1".repeat(65)).parse::<Assertion>(),
+            Err(AssertionError::TooLong {
+                param: "guild_id",
+                max: 64,
+            }
+            .into())
+        );
+    }
+}
@@ -16,6 +16,8 @@
 
 use codec::{Decode, Encode, MaxEncodedLen};
 use scale_info::TypeInfo;
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
 use sp_core::{ecdsa, ed25519, sr25519};
 use sp_runtime::BoundedVec;
 
@@ -92,6 +94,10 @@ pub enum Identity {
     },
     Web2 {
         network: Web2Network,
+        #[cfg_attr(
+            feature = "std",
+            serde(with = "crate::primitives::serde_utils::bounded_string")
+        )]
         address: IdentityString,
     },
 }
@@ -114,21 +120,41 @@ pub enum IdentityMultiSignature {
 #[derive(Encode, Decode, Clone, Debug, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 pub struct TwitterValidationData {
+    #[cfg_attr(
+        feature = "std",
+        serde(with = "crate::primitives::serde_utils::bounded_string")
+    )]
     pub tweet_id: ValidationString,
 }
 
 #[derive(Encode, Decode, Clone, Debug, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 pub struct DiscordValidationData {
+    #[cfg_attr(
+        feature = "std",
+        serde(with = "crate::primitives::serde_utils::bounded_string")
+    )]
     pub channel_id: ValidationString,
+    #[cfg_attr(
+        feature = "std",
+        serde(with = "crate::primitives::serde_utils::bounded_string")
+    )]
     pub message_id: ValidationString,
+    #[cfg_attr(
+        feature = "std",
+        serde(with = "crate::primitives::serde_utils::bounded_string")
+    )]
     pub guild_id: ValidationString,
 }
 
 #[derive(Encode, Decode, Clone, Debug, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 pub struct Web3CommonValidationData {
-    pub message: ValidationString, // or String if under std
+    #[cfg_attr(
+        feature = "std",
+        serde(with = "crate::primitives::serde_utils::bounded_string")
+    )]
+    pub message: ValidationString,
     pub signature: IdentityMultiSignature,
 }
 
@@ -158,6 +184,7 @@ pub enum ValidationData {
 // The context associated with the (litentry-account, did) pair
 // TODO: maybe we have better naming
 #[derive(Clone, Eq, PartialEq, Debug, Encode, Decode, TypeInfo, MaxEncodedLen, Default)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 #[scale_info(skip_type_params(T))]
 #[codec(mel_bound())]
 pub struct IdentityContext {
@@ -184,3 +211,73 @@ impl IdentityContext {
         }
     }
 }
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn identity_serde_works() {
+        let substrate = Identity::Substrate {
+            network: SubstrateNetwork::Litentry,
+            address: Address32::from([1u8; 32]),
+        };
+        let evm = Identity::Evm {
+            network: EvmNetwork::Ethereum,
+            address: Address20::from([2u8; 20]),
+        };
+        let twitter = Identity::Web2 {
+            network: Web2Network::Twitter,
+            address: IdentityString::truncate_from(b"litentry".to_vec()),
+        };
+
+        assert_eq!(
+            serde_json::to_value(&substrate).unwrap(),
+            json!({"Substrate": {"network": "Litentry", "address": format!("0x{}", "01".repeat(32))}})
+        );
+        assert_eq!(
+            serde_json::to_value(&twitter).unwrap(),
+            json!({"Web2": {"network": "Twitter", "address": "litentry"}})
+        );
+        for identity in [substrate, evm, twitter] {
+            let json = serde_json::to_string(&identity).unwrap();
+            assert_eq!(serde_json::from_str::<Identity>(&json).unwrap(), identity);
+        }
+    }
+
+    #[test]
+    fn identity_context_serde_works() {
+        let mut context = IdentityContext::new(3, 5);
+        context.metadata = Some(MetadataOf::truncate_from(b"web3".to_vec()));
+
+        let json = serde_json::to_string(&context).unwrap();
+        assert_eq!(
+            serde_json::from_str::<IdentityContext>(&json).unwrap(),
+            context
+        );
+    }
+
+    #[test]
+    fn validation_data_serde_works() {
+        let discord = ValidationData::Web2(Web2ValidationData::Discord(DiscordValidationData {
+            channel_id: ValidationString::truncate_from(b"919848392035794945".to_vec()),
+            message_id: ValidationString::truncate_from(b"1".to_vec()),
+            guild_id: ValidationString::truncate_from(b"807161594245152800".to_vec()),
+        }));
+        let evm = ValidationData::Web3(Web3ValidationData::Evm(Web3CommonValidationData {
+            message: ValidationString::truncate_from(b"0x1234".to_vec()),
+            signature: IdentityMultiSignature::Ethereum(EthereumSignature([3u8; 65])),
+        }));
+
+        let json = serde_json::to_value(&evm).unwrap();
+        assert_eq!(
+            json["Web3"]["Evm"]["signature"],
+            json!({"Ethereum": "03".repeat(65)})
+        );
+        for data in [discord, evm] {
+            let json = serde_json::to_string(&data).unwrap();
+            assert_eq!(serde_json::from_str::<ValidationData>(&json).unwrap(), data);
+        }
+    }
+}
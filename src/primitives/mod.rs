@@ -14,13 +14,19 @@
 // You should have received a copy of the GNU General Public License
 // along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
 
+pub mod achainable;
 pub mod address;
 pub mod assertion;
+pub mod assertion_info;
 pub mod crypto;
 pub mod enclave;
 pub mod ethereum;
+pub mod format;
 pub mod identity;
 pub mod network;
+pub mod params;
+#[cfg(feature = "std")]
+pub mod serde_utils;
 pub mod vc;
 
 use sp_core::{ConstU32, H256};
@@ -16,6 +16,8 @@
 
 use codec::{Decode, Encode, MaxEncodedLen};
 use scale_info::TypeInfo;
+#[cfg(feature = "std")]
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 use sp_runtime::{traits::ConstU32, BoundedVec};
 // use sp_std::{hash::Hash, vec::Vec};
 use strum::IntoEnumIterator;
@@ -79,6 +81,49 @@ impl Web3Network {
     pub fn is_evm(&self) -> bool {
         matches!(self, Self::Ethereum | Self::Polygon | Self::BSC)
     }
+
+    /// Lower snake case name, e.g. `litentry_rococo`
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Polkadot => "polkadot",
+            Self::Kusama => "kusama",
+            Self::Litentry => "litentry",
+            Self::Litmus => "litmus",
+            Self::LitentryRococo => "litentry_rococo",
+            Self::Khala => "khala",
+            Self::SubstrateTestnet => "substrate_testnet",
+            Self::Ethereum => "ethereum",
+            Self::Polygon => "polygon",
+            Self::BSC => "bsc",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::iter().find(|network| network.name() == name)
+    }
+}
+
+/// Serialized by name, e.g. `"litentry_rococo"`.
+#[cfg(feature = "std")]
+impl Serialize for Web3Network {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(self.name())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'de> Deserialize<'de> for Web3Network {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let name = String::deserialize(deserializer)?;
+        Web3Network::from_name(&name)
+            .ok_or_else(|| de::Error::custom(format!("unknown network {}", name)))
+    }
 }
 
 pub fn all_web3networks() -> Vec<Web3Network> {
@@ -103,6 +148,17 @@ mod tests {
         assert!(networks.len() < MAX_WEB3NETWORK_LEN.try_into().unwrap());
     }
 
+    #[cfg(feature = "std")]
+    #[test]
+    fn web3network_serde_works() {
+        Web3Network::iter().for_each(|network| {
+            let json = serde_json::to_string(&network).unwrap();
+            assert_eq!(json, format!("\"{}\"", network.name()));
+            assert_eq!(serde_json::from_str::<Web3Network>(&json).unwrap(), network);
+        });
+        assert!(serde_json::from_str::<Web3Network>("\"BSC\"").is_err());
+    }
+
     #[test]
     fn is_evm_works() {
         Web3Network::iter().for_each(|network| {
@@ -0,0 +1,463 @@
+// Copyright 2020-2023 Litentry Technologies GmbH.
+// This file is part of Litentry.
+//
+// Litentry is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Litentry is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
+
+use chrono::NaiveDate;
+use sp_core::Get;
+use std::cmp::Ordering;
+use thiserror::Error;
+
+use super::{
+    achainable::{AchainableAssertion, AchainableError, AchainableParam as P},
+    assertion::{AchainableParams, Assertion, ParameterString},
+    network::Web3Network,
+    MaxStringLength,
+};
+
+/**
+ * Checks of the assertion parameters, so a malformed assertion is refused before its
+ * `request_vc` extrinsic is paid for instead of failing in the worker.
+ *
+ * Dates are `YYYY-MM-DD` like `ASSERTION_FROM_DATE`, amounts are non-negative decimal numbers
+ * and percents lie between 0 and 100. A8 counts transactions over `A8_NETWORKS` only.
+ */
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum AssertionError {
+    #[error("{param} is empty")]
+    Empty { param: &'static str },
+    #[error("{param} is longer than {max} bytes")]
+    TooLong { param: &'static str, max: u32 },
+    #[error("{param} {value:?} is not a YYYY-MM-DD date")]
+    InvalidDate { param: &'static str, value: String },
+    #[error("{param} {value:?} is not a decimal amount")]
+    InvalidAmount { param: &'static str, value: String },
+    #[error("{param} {value:?} is not a percent between 0 and 100")]
+    InvalidPercent { param: &'static str, value: String },
+    #[error("{lower} {lower_value:?} must be less than {upper} {upper_value:?}")]
+    OutOfOrder {
+        lower: &'static str,
+        lower_value: String,
+        upper: &'static str,
+        upper_value: String,
+    },
+    #[error("{param} can not include {network:?}")]
+    UnsupportedNetwork {
+        param: &'static str,
+        network: Web3Network,
+    },
+    #[error(transparent)]
+    Achainable(#[from] AchainableError),
+}
+
+/// The networks A8 counts the transactions of.
+pub const A8_NETWORKS: [Web3Network; 6] = [
+    Web3Network::Litentry,
+    Web3Network::Litmus,
+    Web3Network::Polkadot,
+    Web3Network::Kusama,
+    Web3Network::Khala,
+    Web3Network::Ethereum,
+];
+
+/// `value` as the parameter `param`, which can be neither empty nor over `MaxStringLength`.
+pub fn parameter_string(
+    param: &'static str,
+    value: &str,
+) -> Result<ParameterString, AssertionError> {
+    if value.is_empty() {
+        return Err(AssertionError::Empty { param });
+    }
+    ParameterString::try_from(value.as_bytes().to_vec()).map_err(|_| AssertionError::TooLong {
+        param,
+        max: MaxStringLength::get(),
+    })
+}
+
+/// Compare two decimal numbers exactly, amounts may exceed what a float represents.
+pub(crate) fn compare_decimal(left: &str, right: &str) -> Option<Ordering> {
+    let (left_negative, left_int, left_frac) = parse_decimal(left)?;
+    let (right_negative, right_int, right_frac) = parse_decimal(right)?;
+
+    let magnitude = left_int
+        .len()
+        .cmp(&right_int.len())
+        .then_with(|| left_int.cmp(right_int))
+        .then_with(|| left_frac.cmp(right_frac));
+    let is_zero = |int: &str, frac: &str| int.is_empty() && frac.is_empty();
+
+    Some(
+        match (
+            left_negative && !is_zero(left_int, left_frac),
+            right_negative && !is_zero(right_int, right_frac),
+        ) {
+            (false, false) => magnitude,
+            (true, true) => magnitude.reverse(),
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+        },
+    )
+}
+
+/// Sign, integer digits without leading zeros and fraction digits without trailing zeros.
+fn parse_decimal(number: &str) -> Option<(bool, &str, &str)> {
+    let (negative, digits) = match number.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, number),
+    };
+    let (int, frac) = digits.split_once('.').unwrap_or((digits, ""));
+
+    let all_digits = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+    if int.is_empty() || !all_digits(int) || !all_digits(frac) {
+        return None;
+    }
+
+    Some((
+        negative,
+        int.trim_start_matches('0'),
+        frac.trim_end_matches('0'),
+    ))
+}
+
+pub fn check_date(
+    param: &'static str,
+    value: &ParameterString,
+) -> Result<NaiveDate, AssertionError> {
+    let date = text(value);
+    // chrono would accept unpadded months and days
+    if date.len() != "2017-01-01".len() {
+        return Err(AssertionError::InvalidDate {
+            param,
+            value: lossy(value),
+        });
+    }
+    NaiveDate::parse_from_str(date, "%Y-%m-%d").map_err(|_| AssertionError::InvalidDate {
+        param,
+        value: lossy(value),
+    })
+}
+
+pub fn check_amount(param: &'static str, value: &ParameterString) -> Result<(), AssertionError> {
+    let amount = text(value);
+    if amount.starts_with('-') || amount.ends_with('.') || compare_decimal(amount, "0").is_none() {
+        return Err(AssertionError::InvalidAmount {
+            param,
+            value: lossy(value),
+        });
+    }
+    Ok(())
+}
+
+pub fn check_percent(param: &'static str, value: &ParameterString) -> Result<(), AssertionError> {
+    let invalid = || AssertionError::InvalidPercent {
+        param,
+        value: lossy(value),
+    };
+    check_amount(param, value).map_err(|_| invalid())?;
+    if compare_decimal(text(value), "100") == Some(Ordering::Greater) {
+        return Err(invalid());
+    }
+    Ok(())
+}
+
+fn check_non_empty(param: &'static str, value: &ParameterString) -> Result<(), AssertionError> {
+    if value.is_empty() {
+        return Err(AssertionError::Empty { param });
+    }
+    Ok(())
+}
+
+fn check_networks(param: &'static str, networks: &[Web3Network]) -> Result<(), AssertionError> {
+    if networks.is_empty() {
+        return Err(AssertionError::Empty { param });
+    }
+    match networks
+        .iter()
+        .find(|network| !A8_NETWORKS.contains(network))
+    {
+        Some(network) => Err(AssertionError::UnsupportedNetwork {
+            param,
+            network: *network,
+        }),
+        None => Ok(()),
+    }
+}
+
+fn check_less(
+    (lower, lower_value): (P, &ParameterString),
+    (upper, upper_value): (P, &ParameterString),
+    ordering: Option<Ordering>,
+) -> Result<(), AssertionError> {
+    if ordering != Some(Ordering::Less) {
+        return Err(AssertionError::OutOfOrder {
+            lower: lower.name(),
+            lower_value: lossy(lower_value),
+            upper: upper.name(),
+            upper_value: lossy(upper_value),
+        });
+    }
+    Ok(())
+}
+
+fn validate_achainable(params: &AchainableParams) -> Result<(), AssertionError> {
+    AchainableAssertion::check(params)?;
+
+    for param in params.kind().params() {
+        let value = match params.param(*param) {
+            Some(value) => value,
+            None => continue,
+        };
+        match param {
+            P::Amount | P::Amount1 | P::Amount2 => check_amount(param.name(), value)?,
+            // Achainable dates are free: `ASSERTION_FROM_DATE` are the dates the worker queries
+            // the A4, A7, A10 and A11 holdings from, not bounds of the dates given here.
+            P::Date | P::Date1 | P::Date2 | P::StartDate | P::EndDate => {
+                check_date(param.name(), value)?;
+            }
+            P::Percent | P::GreaterThanOrEqualTo | P::LessThanOrEqualTo => {
+                check_percent(param.name(), value)?
+            }
+            P::Token => check_non_empty(param.name(), value)?,
+        }
+    }
+
+    let dates = |date1: &ParameterString, date2: &ParameterString| {
+        // both were checked above
+        let date1 = NaiveDate::parse_from_str(text(date1), "%Y-%m-%d").ok();
+        let date2 = NaiveDate::parse_from_str(text(date2), "%Y-%m-%d").ok();
+        date1.zip(date2).map(|(date1, date2)| date1.cmp(&date2))
+    };
+    match params {
+        AchainableParams::Amounts(p) => check_less(
+            (P::Amount1, &p.amount1),
+            (P::Amount2, &p.amount2),
+            compare_decimal(text(&p.amount1), text(&p.amount2)),
+        ),
+        AchainableParams::BetweenPercents(p) => check_less(
+            (P::GreaterThanOrEqualTo, &p.greater_than_or_equal_to),
+            (P::LessThanOrEqualTo, &p.less_than_or_equal_to),
+            compare_decimal(
+                text(&p.greater_than_or_equal_to),
+                text(&p.less_than_or_equal_to),
+            ),
+        ),
+        AchainableParams::ClassOfYear(p) => check_less(
+            (P::Date1, &p.date1),
+            (P::Date2, &p.date2),
+            dates(&p.date1, &p.date2),
+        ),
+        AchainableParams::DateInterval(p) => check_less(
+            (P::StartDate, &p.start_date),
+            (P::EndDate, &p.end_date),
+            dates(&p.start_date, &p.end_date),
+        ),
+        _ => Ok(()),
+    }
+}
+
+impl Assertion {
+    /// Check the parameters of the assertion, as far as they can be checked without the worker.
+    pub fn validate(&self) -> Result<(), AssertionError> {
+        match self {
+            Assertion::A2(guild_id) => check_non_empty("guild_id", guild_id),
+            Assertion::A3(guild_id, channel_id, role_id) => {
+                check_non_empty("guild_id", guild_id)?;
+                check_non_empty("channel_id", channel_id)?;
+                check_non_empty("role_id", role_id)
+            }
+            Assertion::A4(minimum_amount)
+            | Assertion::A7(minimum_amount)
+            | Assertion::A10(minimum_amount)
+            | Assertion::A11(minimum_amount) => check_amount("minimum_amount", minimum_amount),
+            Assertion::A8(networks) => check_networks("networks", networks),
+            Assertion::Achainable(params) => validate_achainable(params),
+            Assertion::A1 | Assertion::A6 | Assertion::A9 | Assertion::A13(..) | Assertion::A14 => {
+                Ok(())
+            }
+        }
+    }
+
+    pub fn a2(guild_id: &str) -> Result<Self, AssertionError> {
+        Assertion::A2(parameter_string("guild_id", guild_id)?).validated()
+    }
+
+    pub fn a3(guild_id: &str, channel_id: &str, role_id: &str) -> Result<Self, AssertionError> {
+        Assertion::A3(
+            parameter_string("guild_id", guild_id)?,
+            parameter_string("channel_id", channel_id)?,
+            parameter_string("role_id", role_id)?,
+        )
+        .validated()
+    }
+
+    /// LIT holder
+    pub fn a4(minimum_amount: &str) -> Result<Self, AssertionError> {
+        Assertion::A4(parameter_string("minimum_amount", minimum_amount)?).validated()
+    }
+
+    /// DOT holder
+    pub fn a7(minimum_amount: &str) -> Result<Self, AssertionError> {
+        Assertion::A7(parameter_string("minimum_amount", minimum_amount)?).validated()
+    }
+
+    /// WBTC holder
+    pub fn a10(minimum_amount: &str) -> Result<Self, AssertionError> {
+        Assertion::A10(parameter_string("minimum_amount", minimum_amount)?).validated()
+    }
+
+    /// ETH holder
+    pub fn a11(minimum_amount: &str) -> Result<Self, AssertionError> {
+        Assertion::A11(parameter_string("minimum_amount", minimum_amount)?).validated()
+    }
+
+    fn validated(self) -> Result<Self, AssertionError> {
+        self.validate()?;
+        Ok(self)
+    }
+}
+
+fn text(value: &ParameterString) -> &str {
+    std::str::from_utf8(value).unwrap_or_default()
+}
+
+fn lossy(value: &ParameterString) -> String {
+    String::from_utf8_lossy(value).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::primitives::{assertion::AchainableClassOfYear, network::BoundedWeb3Network};
+
+    #[test]
+    fn constructors_validate_params() {
+        assert!(Assertion::a4("10").is_ok());
+        assert!(Assertion::a7("0.5").is_ok());
+        assert_eq!(
+            Assertion::a4("-1"),
+            Err(AssertionError::InvalidAmount {
+                param: "minimum_amount",
+                value: "-1".to_string(),
+            })
+        );
+        assert_eq!(
+            Assertion::a11("ten"),
+            Err(AssertionError::InvalidAmount {
+                param: "minimum_amount",
+                value: "ten".to_string(),
+            })
+        );
+        assert_eq!(
+            Assertion::a3("1", "", "3"),
+            Err(AssertionError::Empty {
+                param: "channel_id"
+            })
+        );
+        assert_eq!(
+            Assertion::a2(&"1".repeat(65)),
+            Err(AssertionError::TooLong {
+                param: "guild_id",
+                max: 64,
+            })
+        );
+    }
+
+    #[test]
+    fn a8_networks_are_validated() {
+        let a8 = |networks: Vec<Web3Network>| {
+            Assertion::A8(BoundedWeb3Network::try_from(networks).unwrap()).validate()
+        };
+        assert_eq!(a8(A8_NETWORKS.to_vec()), Ok(()));
+        assert_eq!(a8(vec![]), Err(AssertionError::Empty { param: "networks" }));
+        for network in [
+            Web3Network::Polygon,
+            Web3Network::SubstrateTestnet,
+            Web3Network::LitentryRococo,
+        ] {
+            assert_eq!(
+                a8(vec![Web3Network::Litentry, network]),
+                Err(AssertionError::UnsupportedNetwork {
+                    param: "networks",
+                    network,
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn achainable_params_are_validated() {
+        let class_of_year = |date1: &str, date2: &str| {
+            Assertion::achainable(
+                "Account created between {dates}",
+                Web3Network::Ethereum,
+                &[(P::Date1, date1), (P::Date2, date2)],
+            )
+        };
+        assert!(class_of_year("2017-01-01", "2023-07-01").is_ok());
+        assert_eq!(
+            class_of_year("2017-1-1", "2023-07-01"),
+            Err(AssertionError::InvalidDate {
+                param: "date1",
+                value: "2017-1-1".to_string(),
+            })
+        );
+        assert_eq!(
+            class_of_year("2023-07-01", "2023-07-01"),
+            Err(AssertionError::OutOfOrder {
+                lower: "date1",
+                lower_value: "2023-07-01".to_string(),
+                upper: "date2",
+                upper_value: "2023-07-01".to_string(),
+            })
+        );
+
+        assert_eq!(
+            Assertion::achainable(
+                "Balance between percents",
+                Web3Network::Polkadot,
+                &[
+                    (P::GreaterThanOrEqualTo, "50"),
+                    (P::LessThanOrEqualTo, "100.5")
+                ],
+            ),
+            Err(AssertionError::InvalidPercent {
+                param: "less_than_or_equal_to",
+                value: "100.5".to_string(),
+            })
+        );
+        assert!(Assertion::achainable(
+            "Balance between {amounts}",
+            Web3Network::Litentry,
+            &[(P::Amount1, "9.5"), (P::Amount2, "10")],
+        )
+        .is_ok());
+
+        // built by hand
+        let assertion =
+            Assertion::Achainable(AchainableParams::ClassOfYear(AchainableClassOfYear {
+                name: ParameterString::truncate_from(b"Account created between {dates}".to_vec()),
+                chain: ParameterString::truncate_from(b"litmus".to_vec()),
+                date1: ParameterString::truncate_from(b"2020-02-30".to_vec()),
+                date2: ParameterString::truncate_from(b"2021-01-01".to_vec()),
+            }));
+        assert_eq!(
+            assertion.validate(),
+            Err(AssertionError::InvalidDate {
+                param: "date1",
+                value: "2020-02-30".to_string(),
+            })
+        );
+    }
+}
@@ -0,0 +1,41 @@
+// Copyright 2020-2023 Litentry Technologies GmbH.
+// This file is part of Litentry.
+//
+// Litentry is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Litentry is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
+
+/// `#[serde(with = "bounded_string")]` for `BoundedVec<u8, _>` fields holding UTF-8 text,
+/// written as strings instead of byte arrays.
+pub mod bounded_string {
+    use serde::{de, Deserialize, Deserializer, Serializer};
+    use sp_core::Get;
+    use sp_runtime::BoundedVec;
+
+    pub fn serialize<S, L>(value: &BoundedVec<u8, L>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let value = std::str::from_utf8(value).map_err(serde::ser::Error::custom)?;
+        serializer.serialize_str(value)
+    }
+
+    pub fn deserialize<'de, D, L>(deserializer: D) -> Result<BoundedVec<u8, L>, D::Error>
+    where
+        D: Deserializer<'de>,
+        L: Get<u32>,
+    {
+        let value = String::deserialize(deserializer)?;
+        BoundedVec::try_from(value.into_bytes())
+            .map_err(|_| de::Error::custom(format!("longer than {} bytes", L::get())))
+    }
+}
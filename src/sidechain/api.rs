@@ -1,5 +1,5 @@
 use super::rpc::SidechainRpcClientTrait;
-use super::{decode_from_rpc_response, json_req, remove_whitespace, SidechainRpc};
+use super::{decode_from_rpc_response, json_request, remove_whitespace, SidechainRpc};
 use crate::primitives::crypto::{DirectRequestStatus, RpcReturnValue, RsaPublicKeyGenerator};
 use crate::utils::hex::FromHexPrefixed;
 use crate::{sidechain::json_resp, ApiClient};
@@ -34,7 +34,7 @@ impl<T: Config> SidechainRpc for ApiClient<T> {
     ]
      */
     fn rpc_methods(&self) -> ApiResult<Vec<String>> {
-        let jsonreq = json_req("rpc_methods", [0_u8; 0], 1);
+        let jsonreq = json_request("rpc_methods", [0_u8; 0]);
         let resp = self.sidechain.request(jsonreq)?;
         let resp = json_resp(resp)?;
         let mut sresult = remove_whitespace(&resp.result);
@@ -54,35 +54,35 @@ impl<T: Config> SidechainRpc for ApiClient<T> {
     { id: "1", jsonrpc: "2.0", result: "hello, world" }
      */
     fn system_version(&self) -> ApiResult<String> {
-        let jsonreq = json_req("system_version", [0_u8; 0], 1);
+        let jsonreq = json_request("system_version", [0_u8; 0]);
         let resp = self.sidechain.request(jsonreq)?;
         let resp = json_resp(resp)?;
         Ok(resp.result)
     }
 
     fn system_name(&self) -> ApiResult<String> {
-        let jsonreq = json_req("system_name", [0_u8; 0], 1);
+        let jsonreq = json_request("system_name", [0_u8; 0]);
         let resp = self.sidechain.request(jsonreq)?;
         let resp = json_resp(resp)?;
         Ok(resp.result)
     }
 
     fn system_health(&self) -> ApiResult<String> {
-        let jsonreq = json_req("system_health", [0_u8; 0], 1);
+        let jsonreq = json_request("system_health", [0_u8; 0]);
         let resp = self.sidechain.request(jsonreq)?;
         let resp = json_resp(resp)?;
         Ok(resp.result)
     }
 
     fn state_get_runtime_version(&self) -> ApiResult<String> {
-        let jsonreq = json_req("state_getRuntimeVersion", [0_u8; 0], 1);
+        let jsonreq = json_request("state_getRuntimeVersion", [0_u8; 0]);
         let resp = self.sidechain.request(jsonreq)?;
         let resp = json_resp(resp)?;
         Ok(resp.result)
     }
 
     fn state_get_metadata(&self) -> ApiResult<RuntimeMetadataPrefixed> {
-        let jsonreq = json_req("state_getMetadata", [0_u8; 0], 1);
+        let jsonreq = json_request("state_getMetadata", [0_u8; 0]);
         let resp = self.sidechain.request(jsonreq)?;
         let rpc_response = json_resp(resp)?;
         let rpc_return_value = RpcReturnValue::from_hex(&rpc_response.result)
@@ -101,14 +101,14 @@ impl<T: Config> SidechainRpc for ApiClient<T> {
     /// {"id":"1","jsonrpc":"2.0","result":"0x3c386c6f63616c686f73743a333434330000"}
     /// "localhost:3443"
     fn author_get_mu_ra_url(&self) -> ApiResult<String> {
-        let jsonreq = json_req("author_getMuRaUrl", [0_u8; 0], 1);
+        let jsonreq = json_request("author_getMuRaUrl", [0_u8; 0]);
         let resp = self.sidechain.request(jsonreq)?;
         let response_message = decode_from_rpc_response(&resp)?;
         Ok(response_message)
     }
 
     fn author_get_shielding_key(&self) -> ApiResult<RsaPublicKey> {
-        let jsonreq = json_req("author_getShieldingKey", [0_u8; 0], 1);
+        let jsonreq = json_request("author_getShieldingKey", [0_u8; 0]);
         let resp = self.sidechain.request(jsonreq)?;
         let shielding_pubkey_string = decode_from_rpc_response(&resp)?;
         Ok(
@@ -121,7 +121,7 @@ impl<T: Config> SidechainRpc for ApiClient<T> {
 
     /// ws://localhost:3000
     fn author_get_untrusted_url(&self) -> ApiResult<String> {
-        let jsonreq = json_req("author_getUntrustedUrl", [0_u8; 0], 1);
+        let jsonreq = json_request("author_getUntrustedUrl", [0_u8; 0]);
         let resp = self.sidechain.request(jsonreq)?;
         let response_message = decode_from_rpc_response(&resp)?;
         Ok(response_message)
@@ -129,7 +129,7 @@ impl<T: Config> SidechainRpc for ApiClient<T> {
 
     /// shards: Base58 format
     fn author_pending_extrinsics(&self, shards: Vec<String>) -> ApiResult<Vec<Vec<Vec<u8>>>> {
-        let jsonreq = json_req("author_pendingExtrinsics", shards, 1);
+        let jsonreq = json_request("author_pendingExtrinsics", shards);
         let resp = self.sidechain.request(jsonreq)?;
         let rpc_response = json_resp(resp)?;
 
@@ -149,10 +149,9 @@ impl<T: Config> SidechainRpc for ApiClient<T> {
         mrenclave_in_base58: String,
         storage_key_in_hex: String,
     ) -> ApiResult<Vec<u8>> {
-        let jsonreq = json_req(
+        let jsonreq = json_request(
             "state_getStorage",
             [mrenclave_in_base58, storage_key_in_hex],
-            1,
         );
         let resp = self.sidechain.request(jsonreq)?;
         let rpc_response = json_resp(resp)?;
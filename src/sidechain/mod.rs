@@ -7,6 +7,7 @@ use serde_json::{json, Value};
 use substrate_api_client::{api::Error as ApiError, api::Result as ApiResult};
 
 use crate::primitives::{address::Address32, identity::Identity};
+use crate::sidechain::rpc::SidechainError;
 use crate::{
     primitives::crypto::{DirectRequestStatus, RpcReturnValue},
     utils::hex::FromHexPrefixed,
@@ -69,17 +70,25 @@ pub struct RpcResponse {
     pub id: u32,
 }
 
-pub fn json_req<S: Serialize>(method: &str, params: S, id: u32) -> Value {
+/// A JSON-RPC request, `SidechainRpcClient` assigns its id when sending it.
+pub fn json_request<S: Serialize>(method: &str, params: S) -> Value {
     json!({
         "method": method,
         "params": params,
         "jsonrpc": "2.0",
-        "id": id.to_string(),
     })
 }
 
+#[deprecated(note = "`SidechainRpcClient` replaces the id when sending, use `json_request`")]
+pub fn json_req<S: Serialize>(method: &str, params: S, id: u32) -> Value {
+    let mut jsonreq = json_request(method, params);
+    jsonreq["id"] = Value::String(id.to_string());
+    jsonreq
+}
+
 pub fn json_resp(resp: String) -> ApiResult<SidechainResp> {
-    let resp: SidechainResp = serde_json::from_str(&resp).unwrap();
+    let resp: SidechainResp =
+        serde_json::from_str(&resp).map_err(|e| SidechainError::Parse(e.to_string()))?;
     Ok(resp)
 }
 
@@ -110,11 +119,10 @@ impl RpcRequest {
 }
 
 fn decode_from_rpc_response(json_rpc_response: &str) -> ApiResult<String> {
-    let rpc_response: SidechainResp = serde_json::from_str(json_rpc_response).unwrap();
-    let rpc_return_value = RpcReturnValue::from_hex(&rpc_response.result).map_err(|_| {
-        let x = hex::FromHexError::OddLength;
-        ApiError::Other(x.into())
-    })?;
+    let rpc_response: SidechainResp = serde_json::from_str(json_rpc_response)
+        .map_err(|e| SidechainError::Parse(e.to_string()))?;
+    let rpc_return_value = RpcReturnValue::from_hex(&rpc_response.result)
+        .map_err(|e| SidechainError::Parse(format!("{:?}", e)))?;
 
     let response_message = String::decode(&mut rpc_return_value.value.as_slice())?;
     match rpc_return_value.status {
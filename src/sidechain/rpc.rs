@@ -1,68 +1,151 @@
 use log::*;
 use openssl::ssl::{SslConnector, SslMethod, SslStream, SslVerifyMode};
 use serde_json::Value;
-use std::fmt::Debug;
-use std::sync::mpsc::{channel, Sender as ThreadOut};
-use substrate_api_client::api::Result as ApiResult;
-use substrate_api_client::rpc::ws_client::RpcMessage;
-
-use ws::{
-    connect, util::TcpStream, CloseCode, Handler, Handshake, Message, Result as WsResult, Sender,
-};
-
-#[allow(clippy::result_large_err)]
-pub trait SidechainHandleMessage {
-    type ThreadMessage;
-
-    fn handle_message(
-        &self,
-        msg: Message,
-        out: Sender,
-        result: ThreadOut<Self::ThreadMessage>,
-    ) -> WsResult<()>;
-}
-
-#[derive(Default, Debug, PartialEq, Eq, Clone)]
-pub struct GetSidechainRequestHandler;
-impl SidechainHandleMessage for GetSidechainRequestHandler {
-    type ThreadMessage = RpcMessage;
-
-    fn handle_message(
-        &self,
-        msg: Message,
-        out: Sender,
-        result: ThreadOut<Self::ThreadMessage>,
-    ) -> WsResult<()> {
-        out.close(CloseCode::Normal)
-            .unwrap_or_else(|_| warn!("Could not close Websocket normally"));
-
-        info!("Got get_request_msg {}", msg);
-        // let result_str = serde_json::from_str(msg.as_text()?)
-        // 	.map(|v: serde_json::Value| Some(v["result"].to_string()))
-        // 	.map_err(RpcClientError::Serde);
-        let result_str =
-            serde_json::from_str(msg.as_text()?).map(|v: serde_json::Value| Some(v.to_string()));
-
-        // result
-        //     .send(result_str).unwrap();
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError, Sender as ThreadOut};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use substrate_api_client::api::{Error as ApiError, Result as ApiResult};
+use thiserror::Error;
 
-        Ok(())
+use ws::{connect, util::TcpStream, CloseCode, Handler, Handshake, Result as WsResult, Sender};
+
+/// How long to wait for the worker to answer a request by default.
+pub const DEFAULT_SIDECHAIN_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Reconnect delays double from `MIN_RECONNECT_BACKOFF` up to `MAX_RECONNECT_BACKOFF`,
+/// and start over once a connection was established.
+pub const MIN_RECONNECT_BACKOFF: Duration = Duration::from_millis(100);
+pub const MAX_RECONNECT_BACKOFF: Duration = Duration::from_secs(30);
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum SidechainError {
+    #[error("connect to {url} failed: {reason}")]
+    Connect { url: String, reason: String },
+    #[error("parse response failed: {0}")]
+    Parse(String),
+    #[error("no response within {0:?}")]
+    Timeout(Duration),
+    #[error("connection closed before a response arrived")]
+    Closed,
+    #[error("request rejected: {0}")]
+    Rejected(String),
+    #[error("updates ended before a final status, last status {0}")]
+    NotFinal(String),
+    #[error("request is not a JSON object: {0}")]
+    InvalidRequest(String),
+}
+
+impl From<SidechainError> for ApiError {
+    fn from(error: SidechainError) -> Self {
+        ApiError::Other(Box::new(error))
+    }
+}
+
+pub type SidechainResult<T> = Result<T, SidechainError>;
+
+/// State shared between the client handles and the thread owning the WebSocket.
+#[derive(Default)]
+struct Connection {
+    out: Mutex<Option<Sender>>,
+    out_changed: Condvar,
+    /// Whether the current connection attempt got through the handshake.
+    opened: AtomicBool,
+    last_error: Mutex<Option<String>>,
+    /// In-flight requests and subscriptions by JSON-RPC id.
+    pending: Mutex<HashMap<u32, ThreadOut<SidechainResult<String>>>>,
+    shutdown: AtomicBool,
+}
+
+impl Connection {
+    fn set_out(&self, out: Option<Sender>) {
+        *self.out.lock().unwrap() = out;
+        self.out_changed.notify_all();
+    }
+
+    /// Wait until the connection is open, for at most `timeout`.
+    fn wait_out(&self, url: &str, timeout: Duration) -> SidechainResult<Sender> {
+        let deadline = Instant::now() + timeout;
+        let mut out = self.out.lock().unwrap();
+        loop {
+            if let Some(out) = out.as_ref() {
+                return Ok(out.clone());
+            }
+
+            let now = Instant::now();
+            if now >= deadline || self.shutdown.load(Ordering::SeqCst) {
+                return Err(match self.last_error.lock().unwrap().clone() {
+                    Some(reason) => SidechainError::Connect {
+                        url: url.to_string(),
+                        reason,
+                    },
+                    None => SidechainError::Timeout(timeout),
+                });
+            }
+
+            out = self
+                .out_changed
+                .wait_timeout(out, deadline - now)
+                .unwrap()
+                .0;
+        }
+    }
+
+    /// Route `msg` to the request or subscription with the same JSON-RPC id.
+    fn dispatch(&self, msg: &str) {
+        let id = match serde_json::from_str::<Value>(msg) {
+            Ok(value) => response_id(&value),
+            Err(e) => {
+                warn!("Dropping unparsable message {}: {:?}", msg, e);
+                return;
+            }
+        };
+        let id = match id {
+            Some(id) => id,
+            None => {
+                warn!("Dropping message without id {}", msg);
+                return;
+            }
+        };
+
+        let mut pending = self.pending.lock().unwrap();
+        if let Some(result) = pending.get(&id) {
+            if result.send(Ok(msg.to_string())).is_err() {
+                pending.remove(&id);
+            }
+        }
+    }
+
+    /// Fail everything in flight, their answers won't come over a new connection.
+    fn fail_pending(&self) {
+        for (_, result) in self.pending.lock().unwrap().drain() {
+            let _ = result.send(Err(SidechainError::Closed));
+        }
     }
 }
 
-pub struct SidechainClient<MessageHandler, ThreadMessage> {
-    pub out: ws::Sender,
-    pub request: String,
-    pub result: ThreadOut<ThreadMessage>,
-    pub message_handler: MessageHandler,
+fn response_id(value: &Value) -> Option<u32> {
+    match &value["id"] {
+        Value::String(id) => id.parse().ok(),
+        Value::Number(id) => id.as_u64().and_then(|id| u32::try_from(id).ok()),
+        _ => None,
+    }
+}
+
+pub struct SidechainClient {
+    pub out: Sender,
+    connection: Arc<Connection>,
 }
 
-impl<MessageHandler: SidechainHandleMessage> Handler
-    for SidechainClient<MessageHandler, MessageHandler::ThreadMessage>
-{
+impl Handler for SidechainClient {
     fn on_open(&mut self, _: Handshake) -> WsResult<()> {
-        println!("sending request: {}", self.request);
-        self.out.send(self.request.clone())?;
+        info!("Sidechain connection opened");
+        self.connection.opened.store(true, Ordering::SeqCst);
+        *self.connection.last_error.lock().unwrap() = None;
+        self.connection.set_out(Some(self.out.clone()));
         Ok(())
     }
 
@@ -73,10 +156,16 @@ impl<MessageHandler: SidechainHandleMessage> Handler
         });
     }
 
+    fn on_error(&mut self, err: ws::Error) {
+        warn!("Sidechain connection error: {:?}", err);
+        *self.connection.last_error.lock().unwrap() = Some(err.to_string());
+        let _ = self.out.shutdown();
+    }
+
     fn on_message(&mut self, msg: ws::Message) -> ws::Result<()> {
-        info!("msg received = {}", msg);
-        self.message_handler
-            .handle_message(msg, self.out.clone(), self.result.clone())
+        debug!("msg received = {}", msg);
+        self.connection.dispatch(msg.as_text()?);
+        Ok(())
     }
 
     fn upgrade_ssl_client(
@@ -106,40 +195,156 @@ impl<MessageHandler: SidechainHandleMessage> Handler
     }
 }
 
-#[derive(Debug, Clone)]
+/// Keep a connection to `url` open until the last client handle is dropped.
+fn run_connection(url: String, connection: Arc<Connection>) {
+    let mut backoff = MIN_RECONNECT_BACKOFF;
+
+    while !connection.shutdown.load(Ordering::SeqCst) {
+        connection.opened.store(false, Ordering::SeqCst);
+        if let Err(e) = connect(url.as_str(), |out| SidechainClient {
+            out,
+            connection: connection.clone(),
+        }) {
+            warn!("Sidechain connection to {} failed: {:?}", url, e);
+            *connection.last_error.lock().unwrap() = Some(e.to_string());
+        }
+
+        connection.set_out(None);
+        connection.fail_pending();
+        if connection.shutdown.load(Ordering::SeqCst) {
+            break;
+        }
+
+        backoff = if connection.opened.load(Ordering::SeqCst) {
+            MIN_RECONNECT_BACKOFF
+        } else {
+            (backoff * 2).min(MAX_RECONNECT_BACKOFF)
+        };
+        info!("Reconnecting to {} in {:?}", url, backoff);
+        thread::sleep(backoff);
+    }
+}
+
+/// Closes the connection once every clone of a `SidechainRpcClient` is gone.
+struct ShutdownOnDrop(Arc<Connection>);
+
+impl Drop for ShutdownOnDrop {
+    fn drop(&mut self) {
+        self.0.shutdown.store(true, Ordering::SeqCst);
+        if let Some(out) = self.0.out.lock().unwrap().take() {
+            let _ = out.close(CloseCode::Normal);
+        }
+        self.0.out_changed.notify_all();
+    }
+}
+
+/// A JSON-RPC client for the TEE worker, multiplexing all requests over one WebSocket.
+///
+/// Requests get a unique id and answers are routed back by that id, so clones of a client can
+/// be used from several threads at once. A dropped connection is re-established in the background.
+#[derive(Clone)]
 pub struct SidechainRpcClient {
     url: String,
+    timeout: Duration,
+    next_id: Arc<AtomicU32>,
+    connection: Arc<Connection>,
+    _shutdown: Arc<ShutdownOnDrop>,
+}
+
+impl fmt::Debug for SidechainRpcClient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SidechainRpcClient")
+            .field("url", &self.url)
+            .field("timeout", &self.timeout)
+            .finish()
+    }
 }
 
 impl SidechainRpcClient {
     pub fn new(url: &str) -> SidechainRpcClient {
+        let connection = Arc::new(Connection::default());
+
+        let url = url.to_string();
+        let background_connection = connection.clone();
+        let background_url = url.clone();
+        thread::spawn(move || run_connection(background_url, background_connection));
+
         SidechainRpcClient {
-            url: url.to_string(),
+            url,
+            timeout: DEFAULT_SIDECHAIN_TIMEOUT,
+            next_id: Arc::new(AtomicU32::new(1)),
+            _shutdown: Arc::new(ShutdownOnDrop(connection.clone())),
+            connection,
         }
     }
 
-    fn direct_rpc_request<MessageHandler>(
-        &self,
-        jsonreq: String,
-        message_handler: MessageHandler,
-    ) -> ApiResult<MessageHandler::ThreadMessage>
-    where
-        MessageHandler: SidechainHandleMessage + Clone + Send + 'static,
-        MessageHandler::ThreadMessage: Send + Sync + Debug,
-    {
-        println!(">>> jsonreq: {:?}", jsonreq);
-        println!("self.url: {}", self.url);
+    /// Give up on a request the worker has not answered within `timeout`.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Send `jsonreq`, a JSON object, under a fresh id. Every message the worker sends back with
+    /// that id arrives on the returned subscription.
+    pub fn subscribe(&self, mut jsonreq: Value) -> SidechainResult<SidechainSubscription> {
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        jsonreq
+            .as_object_mut()
+            .ok_or_else(|| SidechainError::InvalidRequest(jsonreq.to_string()))?
+            .insert("id".to_string(), Value::String(id.to_string()));
+        debug!(">>> jsonreq: {:?}", jsonreq);
 
         let (result_in, result_out) = channel();
-        connect(self.url.as_str(), |out| SidechainClient {
-            out,
-            request: jsonreq.clone(),
-            result: result_in.clone(),
-            message_handler: message_handler.clone(),
-        })
-        .unwrap();
+        self.connection
+            .pending
+            .lock()
+            .unwrap()
+            .insert(id, result_in);
+        let subscription = SidechainSubscription {
+            id,
+            timeout: self.timeout,
+            receiver: result_out,
+            connection: self.connection.clone(),
+        };
+
+        let out = self.connection.wait_out(&self.url, self.timeout)?;
+        out.send(jsonreq.to_string())
+            .map_err(|_| SidechainError::Closed)?;
+
+        Ok(subscription)
+    }
+}
+
+/// The messages answering one request, see `SidechainRpcClient::subscribe`.
+pub struct SidechainSubscription {
+    id: u32,
+    timeout: Duration,
+    receiver: Receiver<SidechainResult<String>>,
+    connection: Arc<Connection>,
+}
+
+impl SidechainSubscription {
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    /// The next message, waiting at most for the client timeout.
+    pub fn next_message(&self) -> SidechainResult<String> {
+        self.next_message_timeout(self.timeout)
+    }
 
-        Ok(result_out.recv().unwrap())
+    pub fn next_message_timeout(&self, timeout: Duration) -> SidechainResult<String> {
+        match self.receiver.recv_timeout(timeout) {
+            Ok(message) => message,
+            Err(RecvTimeoutError::Timeout) => Err(SidechainError::Timeout(timeout)),
+            Err(RecvTimeoutError::Disconnected) => Err(SidechainError::Closed),
+        }
+    }
+}
+
+impl Drop for SidechainSubscription {
+    fn drop(&mut self) {
+        self.connection.pending.lock().unwrap().remove(&self.id);
     }
 }
 
@@ -148,6 +353,45 @@ pub trait SidechainRpcClientTrait {
 }
 impl SidechainRpcClientTrait for SidechainRpcClient {
     fn request(&self, jsonreq: Value) -> ApiResult<String> {
-        Ok(self.direct_rpc_request(jsonreq.to_string(), GetSidechainRequestHandler::default())??)
+        let response = self.subscribe(jsonreq)?.next_message()?;
+        Ok(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dispatch_routes_by_id() {
+        let connection = Connection::default();
+        let (first_in, first_out) = channel();
+        let (second_in, second_out) = channel();
+        connection.pending.lock().unwrap().insert(1, first_in);
+        connection.pending.lock().unwrap().insert(2, second_in);
+
+        connection.dispatch(r#"{"id":"2","jsonrpc":"2.0","result":"0x02"}"#);
+        connection.dispatch(r#"{"id":1,"jsonrpc":"2.0","result":"0x01"}"#);
+        connection.dispatch(r#"{"id":"3","jsonrpc":"2.0","result":"0x03"}"#);
+
+        assert!(first_out.try_recv().unwrap().unwrap().contains("0x01"));
+        assert!(second_out.try_recv().unwrap().unwrap().contains("0x02"));
+        assert!(first_out.try_recv().is_err());
+        assert!(second_out.try_recv().is_err());
+
+        connection.fail_pending();
+        assert_eq!(first_out.try_recv().unwrap(), Err(SidechainError::Closed));
+        assert!(connection.pending.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn subscribe_rejects_requests_that_are_not_objects() {
+        let client = SidechainRpcClient::new("ws://127.0.0.1:1");
+        for jsonreq in [Value::Null, serde_json::json!(["rpc_methods"])] {
+            assert_eq!(
+                client.subscribe(jsonreq.clone()).err(),
+                Some(SidechainError::InvalidRequest(jsonreq.to_string()))
+            );
+        }
     }
 }
@@ -0,0 +1,90 @@
+//! A minimal runtime metadata fixture with the Litentry pallets the SDK composes calls for.
+//! The pallet indexes are arbitrary test values, the SDK reads the real ones from the runtime
+//! metadata. Call indexes follow the declaration order of the calls below.
+
+use crate::{
+    identity_management::IDENTITY_PALLET_NAME,
+    primitives::{address::Address32, assertion::Assertion},
+    vc_management::VC_PALLET_NAME,
+};
+use codec::Encode;
+use frame_metadata::{
+    v14::{ExtrinsicMetadata, PalletCallMetadata, PalletMetadata, RuntimeMetadataV14},
+    RuntimeMetadata, RuntimeMetadataPrefixed, META_RESERVED,
+};
+use scale_info::{meta_type, TypeInfo};
+use sp_core::H256;
+use substrate_api_client::ac_node_api::Metadata;
+
+pub const IDENTITY_PALLET_INDEX: u8 = 64;
+pub const VC_PALLET_INDEX: u8 = 65;
+
+#[derive(Encode, TypeInfo)]
+#[allow(non_camel_case_types, dead_code)]
+pub enum IdentityManagementCall {
+    #[codec(index = 0)]
+    add_delegatee { account: Address32 },
+    #[codec(index = 1)]
+    remove_delegatee { account: Address32 },
+    #[codec(index = 2)]
+    set_user_shielding_key { shard: H256, encrypted_key: Vec<u8> },
+    #[codec(index = 3)]
+    create_identity {
+        shard: H256,
+        user: Address32,
+        encrypted_identity: Vec<u8>,
+        encrypted_metadata: Option<Vec<u8>>,
+    },
+    #[codec(index = 4)]
+    remove_identity {
+        shard: H256,
+        encrypted_identity: Vec<u8>,
+    },
+    #[codec(index = 5)]
+    verify_identity {
+        shard: H256,
+        encrypted_identity: Vec<u8>,
+        encrypted_validation_data: Vec<u8>,
+    },
+}
+
+#[derive(Encode, TypeInfo)]
+#[allow(non_camel_case_types, dead_code)]
+pub enum VCManagementCall {
+    #[codec(index = 0)]
+    request_vc { shard: H256, assertion: Assertion },
+    #[codec(index = 1)]
+    disable_vc { index: H256 },
+    #[codec(index = 2)]
+    revoke_vc { index: H256 },
+}
+
+fn pallet<Call: TypeInfo + 'static>(name: &'static str, index: u8) -> PalletMetadata {
+    PalletMetadata {
+        name,
+        storage: None,
+        calls: Some(PalletCallMetadata {
+            ty: meta_type::<Call>(),
+        }),
+        event: None,
+        constants: vec![],
+        error: None,
+        index,
+    }
+}
+
+pub fn mock_metadata() -> Metadata {
+    let pallets = vec![
+        pallet::<IdentityManagementCall>(IDENTITY_PALLET_NAME, IDENTITY_PALLET_INDEX),
+        pallet::<VCManagementCall>(VC_PALLET_NAME, VC_PALLET_INDEX),
+    ];
+    let extrinsic = ExtrinsicMetadata {
+        ty: meta_type::<()>(),
+        version: 4,
+        signed_extensions: vec![],
+    };
+    let runtime_metadata = RuntimeMetadataV14::new(pallets, extrinsic, meta_type::<()>());
+    let prefixed = RuntimeMetadataPrefixed(META_RESERVED, RuntimeMetadata::V14(runtime_metadata));
+
+    Metadata::try_from(prefixed).expect("valid metadata fixture")
+}
@@ -6,6 +6,9 @@ pub mod hex;
 pub mod identity;
 pub mod vc;
 
+#[cfg(test)]
+pub(crate) mod mock_metadata;
+
 pub fn print_passed() {
     println!(" 🎉 All testcases passed!");
 }
@@ -1,25 +1,32 @@
 use super::VcManagementApi;
 use crate::{
     primitives::{assertion::Assertion, MrEnclave},
-    vc_management::xtbuilder::VcManagementXtBuilder,
-    ApiClient, SendExtrinsic,
+    vc_management::{
+        events::{RequestVCFailedEvent, VCDisabledEvent, VCIssuedEvent, VCRevokedEvent},
+        xtbuilder::VcManagementXtBuilder,
+    },
+    ApiClient, ExtrinsicOutcome, SendExtrinsic,
 };
 use sp_core::H256;
-use substrate_api_client::ac_primitives::Config;
+use substrate_api_client::{ac_primitives::Config, api::Result as ApiResult};
 
-impl<T: Config> VcManagementApi for ApiClient<T> {
-    fn request_vc(&self, shard: &MrEnclave, assertion: &Assertion) {
-        let xt = self.build_extrinsic_request_vc(shard, assertion);
-        self.send_extrinsic(xt);
+impl<T: Config<Hash = H256>> VcManagementApi for ApiClient<T> {
+    fn request_vc(
+        &self,
+        shard: &MrEnclave,
+        assertion: &Assertion,
+    ) -> ApiResult<ExtrinsicOutcome<VCIssuedEvent>> {
+        let xt = self.build_extrinsic_request_vc(shard, assertion)?;
+        self.send_request_extrinsic::<_, _, RequestVCFailedEvent>(xt)
     }
 
-    fn disable_vc(&self, vc_index: &H256) {
-        let xt = self.build_extrinsic_disable_vc(vc_index);
-        self.send_extrinsic(xt);
+    fn disable_vc(&self, vc_index: &H256) -> ApiResult<ExtrinsicOutcome<VCDisabledEvent>> {
+        let xt = self.build_extrinsic_disable_vc(vc_index)?;
+        self.send_extrinsic_for_event(xt)
     }
 
-    fn revoke_vc(&self, vc_index: &H256) {
-        let xt = self.build_extrinsic_revoke_vc(vc_index);
-        self.send_extrinsic(xt);
+    fn revoke_vc(&self, vc_index: &H256) -> ApiResult<ExtrinsicOutcome<VCRevokedEvent>> {
+        let xt = self.build_extrinsic_revoke_vc(vc_index)?;
+        self.send_extrinsic_for_event(xt)
     }
 }
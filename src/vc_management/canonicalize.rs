@@ -0,0 +1,205 @@
+use serde_json::{Map, Number, Value};
+use thiserror::Error;
+
+/**
+ * The message a VC proof signs is the credential without its `proof`, serialized to bytes.
+ * Re-serializing a `Credential` only reproduces those bytes as long as our struct matches the
+ * worker's field by field, so the message is rebuilt from the JSON document instead.
+ */
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Canonicalization {
+    /// Compact JSON keeping the members in document order, the form the TEE worker signs.
+    DocumentOrder,
+    /// JSON Canonicalization Scheme, RFC 8785.
+    Jcs,
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum CanonicalizeError {
+    #[error("credential is not a JSON object")]
+    NotAnObject,
+    #[error("invalid JSON: {0}")]
+    Json(String),
+}
+
+/// The message the proof of `vc` signs: `vc` without `proof`, canonicalized with `scheme`.
+pub fn signing_input(vc: &Value, scheme: Canonicalization) -> Result<Vec<u8>, CanonicalizeError> {
+    // Collected rather than `remove`d, which may reorder the members.
+    let vc: Map<String, Value> = vc
+        .as_object()
+        .ok_or(CanonicalizeError::NotAnObject)?
+        .iter()
+        .filter(|(name, _)| name.as_str() != "proof")
+        .map(|(name, value)| (name.clone(), value.clone()))
+        .collect();
+
+    Ok(canonicalize(&Value::Object(vc), scheme).into_bytes())
+}
+
+/// Same as `signing_input`, for a credential as received from the worker.
+pub fn signing_input_from_str(
+    vc_json: &str,
+    scheme: Canonicalization,
+) -> Result<Vec<u8>, CanonicalizeError> {
+    let vc: Value =
+        serde_json::from_str(vc_json).map_err(|e| CanonicalizeError::Json(e.to_string()))?;
+    signing_input(&vc, scheme)
+}
+
+pub fn canonicalize(value: &Value, scheme: Canonicalization) -> String {
+    let mut out = String::new();
+    match scheme {
+        Canonicalization::DocumentOrder => out.push_str(&value.to_string()),
+        Canonicalization::Jcs => write_jcs(value, &mut out),
+    }
+    out
+}
+
+fn write_jcs(value: &Value, out: &mut String) {
+    match value {
+        Value::Null | Value::Bool(_) | Value::String(_) => out.push_str(&value.to_string()),
+        Value::Number(number) => out.push_str(&jcs_number(number)),
+        Value::Array(values) => {
+            out.push('[');
+            for (i, value) in values.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_jcs(value, out);
+            }
+            out.push(']');
+        }
+        Value::Object(members) => write_jcs_object(members, out),
+    }
+}
+
+/// Members are sorted by the UTF-16 code units of their names.
+fn write_jcs_object(members: &Map<String, Value>, out: &mut String) {
+    let mut members: Vec<_> = members.iter().collect();
+    members.sort_by(|(a, _), (b, _)| a.encode_utf16().cmp(b.encode_utf16()));
+
+    out.push('{');
+    for (i, (name, value)) in members.into_iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        out.push_str(&Value::String(name.clone()).to_string());
+        out.push(':');
+        write_jcs(value, out);
+    }
+    out.push('}');
+}
+
+/// Numbers are written like ECMAScript `Number.prototype.toString`.
+/// Integers are kept as they are, even beyond 2^53 where ECMAScript would round them.
+fn jcs_number(number: &Number) -> String {
+    let float = match number.as_f64() {
+        Some(float) if number.is_f64() => float,
+        _ => return number.to_string(),
+    };
+
+    if float == 0.0 {
+        return "0".to_string();
+    }
+    let abs = float.abs();
+    if (1e-6..1e21).contains(&abs) {
+        return float.to_string();
+    }
+
+    // `{:e}` gives the shortest mantissa, only the exponent sign differs from ECMAScript.
+    let formatted = format!("{:e}", float);
+    match formatted.split_once('e') {
+        Some((mantissa, exponent)) if !exponent.starts_with('-') => {
+            format!("{}e+{}", mantissa, exponent)
+        }
+        _ => formatted,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sp_core::{ed25519, Pair};
+
+    const SAMPLES: [(&str, &str); 10] = [
+        ("a1", include_str!("../../docs/vc-examples/a1.json")),
+        ("a2", include_str!("../../docs/vc-examples/a2.json")),
+        ("a3", include_str!("../../docs/vc-examples/a3.json")),
+        ("a4", include_str!("../../docs/vc-examples/a4.json")),
+        ("a5", include_str!("../../docs/vc-examples/a5.json")),
+        ("a6", include_str!("../../docs/vc-examples/a6.json")),
+        ("a7", include_str!("../../docs/vc-examples/a7.json")),
+        ("a8", include_str!("../../docs/vc-examples/a8.json")),
+        ("a10", include_str!("../../docs/vc-examples/a10.json")),
+        ("a11", include_str!("../../docs/vc-examples/a11.json")),
+    ];
+
+    fn verify_sample(vc_json: &str, scheme: Canonicalization) -> bool {
+        let vc: Value = serde_json::from_str(vc_json).unwrap();
+        let proof = &vc["proof"];
+        let public = hex::decode(proof["verificationMethod"].as_str().unwrap()).unwrap();
+        let signature = hex::decode(proof["proofValue"].as_str().unwrap()).unwrap();
+
+        let public = ed25519::Public::from_raw(public.try_into().unwrap());
+        let signature = ed25519::Signature::from_slice(&signature).unwrap();
+        let message = signing_input(&vc, scheme).unwrap();
+
+        ed25519::Pair::verify(&signature, message, &public)
+    }
+
+    #[test]
+    fn signed_samples_verify_in_document_order() {
+        for (name, vc_json) in SAMPLES {
+            assert!(
+                verify_sample(vc_json, Canonicalization::DocumentOrder),
+                "{name} does not verify"
+            );
+        }
+    }
+
+    #[test]
+    fn signed_samples_are_not_jcs() {
+        for (_, vc_json) in SAMPLES {
+            assert!(!verify_sample(vc_json, Canonicalization::Jcs));
+        }
+    }
+
+    #[test]
+    fn signing_input_drops_proof() {
+        let vc = serde_json::json!({"b": 1, "proof": {"proofValue": "00"}, "a": [true, null]});
+
+        assert_eq!(
+            signing_input(&vc, Canonicalization::DocumentOrder).unwrap(),
+            br#"{"b":1,"a":[true,null]}"#
+        );
+        assert_eq!(
+            signing_input(&serde_json::json!([]), Canonicalization::Jcs),
+            Err(CanonicalizeError::NotAnObject)
+        );
+    }
+
+    #[test]
+    fn jcs_works() {
+        // RFC 8785, 3.2.3
+        let value: Value = serde_json::from_str(
+            r#"{"€": "Euro Sign", "\r": "Carriage Return", "😀": "Emoji", "1": "One",
+                "\u0080": "Control", "ö": "Latin Small Letter O With Diaeresis",
+                "numbers": [333333333.33333329, 1E30, 4.50, 2e-3, 0.000000000000000000000000001],
+                "literals": [null, true, false], "string": "€$\u000F\u000aA'B\"\\\\\"\/"}"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            canonicalize(&value, Canonicalization::Jcs),
+            concat!(
+                r#"{"\r":"Carriage Return","1":"One","literals":[null,true,false],"#,
+                r#""numbers":[333333333.3333333,1e+30,4.5,0.002,1e-27],"#,
+                r#""string":"€$\u000f\nA'B\"\\\\\"/","#,
+                "\"\u{80}\":\"Control\",\"ö\":\"Latin Small Letter O With Diaeresis\",",
+                r#""€":"Euro Sign","😀":"Emoji"}"#
+            )
+        );
+        assert_eq!(jcs_number(&Number::from_f64(-0.0).unwrap()), "0");
+    }
+}
@@ -1,6 +1,7 @@
 use super::VC_PALLET_NAME;
-use crate::primitives::{
-    assertion::Assertion, crypto::AesOutput, vc::ErrorDetail, AccountId, VCIndex,
+use crate::{
+    api_client_patch::event::{FilterableEvent, RequestFailed},
+    primitives::{assertion::Assertion, crypto::AesOutput, vc::ErrorDetail, AccountId, VCIndex},
 };
 use codec::Decode;
 use scale_info::TypeInfo;
@@ -22,6 +23,16 @@ impl StaticEvent for VCIssuedEvent {
     const EVENT: &'static str = "VCIssued";
 }
 
+impl FilterableEvent for VCIssuedEvent {
+    fn account(&self) -> Option<&AccountId> {
+        Some(&self.account)
+    }
+
+    fn req_ext_hash(&self) -> Option<H256> {
+        Some(self.req_ext_hash)
+    }
+}
+
 /// VCDisabled
 #[derive(Decode, Debug, PartialEq, Eq)]
 pub struct VCDisabledEvent {
@@ -34,6 +45,12 @@ impl StaticEvent for VCDisabledEvent {
     const EVENT: &'static str = "VCDisabled";
 }
 
+impl FilterableEvent for VCDisabledEvent {
+    fn account(&self) -> Option<&AccountId> {
+        Some(&self.account)
+    }
+}
+
 /// VCRevoked
 #[derive(Decode, Debug, PartialEq, Eq)]
 pub struct VCRevokedEvent {
@@ -46,7 +63,13 @@ impl StaticEvent for VCRevokedEvent {
     const EVENT: &'static str = "VCRevoked";
 }
 
-/// VCRevoked
+impl FilterableEvent for VCRevokedEvent {
+    fn account(&self) -> Option<&AccountId> {
+        Some(&self.account)
+    }
+}
+
+/// RequestVCFailed
 #[derive(Decode, Debug, PartialEq, Eq)]
 pub struct RequestVCFailedEvent {
     pub account: Option<AccountId>,
@@ -60,6 +83,27 @@ impl StaticEvent for RequestVCFailedEvent {
     const EVENT: &'static str = "RequestVCFailed";
 }
 
+impl FilterableEvent for RequestVCFailedEvent {
+    fn account(&self) -> Option<&AccountId> {
+        self.account.as_ref()
+    }
+
+    fn req_ext_hash(&self) -> Option<H256> {
+        Some(self.req_ext_hash)
+    }
+}
+
+impl From<RequestVCFailedEvent> for RequestFailed {
+    fn from(event: RequestVCFailedEvent) -> Self {
+        RequestFailed {
+            event: RequestVCFailedEvent::EVENT,
+            account: event.account,
+            detail: event.detail,
+            req_ext_hash: event.req_ext_hash,
+        }
+    }
+}
+
 /// Error
 #[derive(Decode, Debug, PartialEq, Eq)]
 pub struct VCManagementError;
@@ -0,0 +1,61 @@
+//! Credentials of `docs/vc-examples` for tests.
+//!
+//! The samples were issued before block numbers were replaced with timestamps. They keep their
+//! `issuanceBlockNumber` and `createdBlockNumber` fields, renaming them would invalidate the
+//! proofs the canonicalization tests check, so they are renamed when parsed instead.
+
+use crate::{
+    primitives::{
+        enclave::{Enclave, SgxBuildMode, SgxEnclaveMetadata},
+        vc::Credential,
+        AccountId,
+    },
+    utils::enclave::mrenclave_from_bs58,
+    vc_management::canonicalize::{signing_input, Canonicalization},
+};
+use serde_json::Value;
+use sp_core::{ed25519, Pair};
+
+pub const A1: &str = include_str!("../../docs/vc-examples/a1.json");
+
+/// Parse a sample of `docs/vc-examples` into a `Credential`.
+pub fn sample(vc_json: &str) -> Credential {
+    let mut value: Value = serde_json::from_str(vc_json).unwrap();
+    let vc = value.as_object_mut().unwrap();
+    if let Some(issuance) = vc.remove("issuanceBlockNumber") {
+        vc.insert("issuanceTimestamp".to_string(), issuance);
+    }
+    if let Some(proof) = vc.get_mut("proof").and_then(Value::as_object_mut) {
+        if let Some(created) = proof.remove("createdBlockNumber") {
+            proof.insert("createdTimestamp".to_string(), created);
+        }
+    }
+
+    serde_json::from_value(value).unwrap()
+}
+
+/// The A1 sample, issued to Alice.
+pub fn credential() -> Credential {
+    sample(A1)
+}
+
+/// The credential `value` as a worker holding `pair` would return it, with its `proofValue` set.
+pub fn signed(mut value: Value, pair: &ed25519::Pair) -> String {
+    let message = signing_input(&value, Canonicalization::DocumentOrder).unwrap();
+    value["proof"]["proofValue"] = Value::String(hex::encode(pair.sign(&message).0));
+    value.to_string()
+}
+
+/// The registered enclave that issued `vc`.
+pub fn enclave(vc: &Credential) -> Enclave<AccountId, String> {
+    Enclave {
+        pubkey: AccountId::new([0u8; 32]),
+        mr_enclave: mrenclave_from_bs58(vc.issuer.mrenclave.clone()).unwrap(),
+        timestamp: 0,
+        url: String::new(),
+        shielding_key: None,
+        vc_pubkey: Some(hex::decode(&vc.issuer.id).unwrap()),
+        sgx_mode: SgxBuildMode::Debug,
+        sgx_metadata: SgxEnclaveMetadata::default(),
+    }
+}
@@ -0,0 +1,304 @@
+use crate::{
+    api_client_patch::parachain::ParachainPatch,
+    primitives::vc::Credential,
+    vc_management::verify::{hex_eq, verify_vc_value_proof, VerificationError},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sp_core::{ed25519, Pair};
+use substrate_api_client::{api::Error as ApiClientError, api::Result as ApiResult};
+use thiserror::Error;
+
+/**
+ * VC-JWT encoding of credentials, https://www.w3.org/TR/vc-data-model/#json-web-token
+ *
+ * The JWS is signed with EdDSA by the issuing enclave's vc key, so only the issuer can encode
+ * credentials. The `vc` claim carries the credential as the worker returned it, `proof`
+ * included: the credential can still be verified, stored or presented on its own once decoded.
+ */
+
+pub const JWT_ALGORITHM: &str = "EdDSA";
+pub const JWT_TYPE: &str = "JWT";
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum JwtError {
+    #[error("malformed JWT: {0}")]
+    Malformed(String),
+    #[error("unsupported JWT algorithm {0}")]
+    UnsupportedAlgorithm(String),
+    #[error("unsupported JWT type {0}")]
+    UnsupportedType(String),
+    #[error("JWT key id {kid} is not the credential issuer {issuer}")]
+    KeyIdMismatch { kid: String, issuer: String },
+    #[error("JWT claim {0} does not match the credential")]
+    ClaimMismatch(&'static str),
+    #[error("signature does not match the vc_pubkey")]
+    BadSignature,
+    #[error("credential proof: {0}")]
+    Proof(#[from] VerificationError),
+    #[error("credential can not be serialized: {0}")]
+    Serialize(String),
+}
+
+impl From<JwtError> for ApiClientError {
+    fn from(error: JwtError) -> Self {
+        ApiClientError::Other(Box::new(error))
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct JwtHeader {
+    pub alg: String,
+    pub typ: String,
+    /// Hex vc_pubkey of the issuing enclave
+    pub kid: String,
+}
+
+/// The registered claims are taken from the credential, times are in seconds.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct JwtClaims {
+    pub iss: String,
+    pub sub: String,
+    pub jti: String,
+    pub nbf: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exp: Option<u64>,
+    /// The credential as the worker returned it, in document order
+    pub vc: Value,
+}
+
+impl JwtClaims {
+    /// The claims of `vc_json`, the credential as the worker returned it.
+    pub fn new(vc_json: &str) -> Result<Self, JwtError> {
+        let vc: Value =
+            serde_json::from_str(vc_json).map_err(|e| JwtError::Serialize(e.to_string()))?;
+        let credential = credential(&vc)?;
+
+        Ok(JwtClaims {
+            iss: credential.issuer.id.clone(),
+            sub: credential.credential_subject.id.clone(),
+            jti: credential.id.clone(),
+            nbf: credential.issuance_timestamp / 1000,
+            exp: credential
+                .expiration_timestamp
+                .map(|timestamp| timestamp / 1000),
+            vc,
+        })
+    }
+
+    /// The credential of the `vc` claim, once the registered claims are checked against it.
+    fn check(&self) -> Result<Credential, JwtError> {
+        let vc = credential(&self.vc)?;
+        if self.iss != vc.issuer.id {
+            return Err(JwtError::ClaimMismatch("iss"));
+        }
+        if self.sub != vc.credential_subject.id {
+            return Err(JwtError::ClaimMismatch("sub"));
+        }
+        if self.jti != vc.id {
+            return Err(JwtError::ClaimMismatch("jti"));
+        }
+        if self.nbf != vc.issuance_timestamp / 1000 {
+            return Err(JwtError::ClaimMismatch("nbf"));
+        }
+        if self.exp != vc.expiration_timestamp.map(|timestamp| timestamp / 1000) {
+            return Err(JwtError::ClaimMismatch("exp"));
+        }
+        Ok(vc)
+    }
+}
+
+fn credential(vc: &Value) -> Result<Credential, JwtError> {
+    serde_json::from_value(vc.clone()).map_err(|e| JwtError::Malformed(e.to_string()))
+}
+
+/// The `header.payload` part of the VC-JWT of `vc_json`, the message the JWS signs.
+pub fn vc_jwt_signing_input(vc_json: &str) -> Result<String, JwtError> {
+    let claims = JwtClaims::new(vc_json)?;
+    let header = JwtHeader {
+        alg: JWT_ALGORITHM.to_string(),
+        typ: JWT_TYPE.to_string(),
+        kid: claims.iss.clone(),
+    };
+    let header = serde_json::to_vec(&header).map_err(|e| JwtError::Serialize(e.to_string()))?;
+    let claims = serde_json::to_vec(&claims).map_err(|e| JwtError::Serialize(e.to_string()))?;
+
+    Ok(format!(
+        "{}.{}",
+        base64_encode(&header),
+        base64_encode(&claims)
+    ))
+}
+
+/// Encode `vc_json`, the credential as the worker returned it, as a VC-JWT signed by `sign` with
+/// the vc key of the issuing enclave.
+pub fn encode_vc_jwt<F>(vc_json: &str, sign: F) -> Result<String, JwtError>
+where
+    F: FnOnce(&[u8]) -> ed25519::Signature,
+{
+    let signing_input = vc_jwt_signing_input(vc_json)?;
+    let signature = sign(signing_input.as_bytes());
+
+    Ok(format!("{}.{}", signing_input, base64_encode(&signature.0)))
+}
+
+/// Verify the EdDSA signature of the VC-JWT `token` with `vc_pubkey` and return its credential.
+/// The header must name the credential issuer as `kid`, and the `proof` of the credential must
+/// verify with `vc_pubkey` as well.
+pub fn decode_vc_jwt(token: &str, vc_pubkey: &ed25519::Public) -> Result<Credential, JwtError> {
+    let (signing_input, signature) = token
+        .rsplit_once('.')
+        .ok_or_else(|| JwtError::Malformed("missing signature".to_string()))?;
+    let (header, claims) = signing_input
+        .split_once('.')
+        .ok_or_else(|| JwtError::Malformed("missing payload".to_string()))?;
+
+    let header: JwtHeader = decode_part(header)?;
+    if header.alg != JWT_ALGORITHM {
+        return Err(JwtError::UnsupportedAlgorithm(header.alg));
+    }
+    if header.typ != JWT_TYPE {
+        return Err(JwtError::UnsupportedType(header.typ));
+    }
+
+    let signature = base64_decode(signature)?;
+    let signature = ed25519::Signature::from_slice(&signature)
+        .ok_or_else(|| JwtError::Malformed(format!("{} bytes signature", signature.len())))?;
+    if !ed25519::Pair::verify(&signature, signing_input.as_bytes(), vc_pubkey) {
+        return Err(JwtError::BadSignature);
+    }
+
+    let claims: JwtClaims = decode_part(claims)?;
+    let vc = claims.check()?;
+    if !hex_eq(&header.kid, &vc.issuer.id) {
+        return Err(JwtError::KeyIdMismatch {
+            kid: header.kid,
+            issuer: vc.issuer.id,
+        });
+    }
+    verify_vc_value_proof(vc_pubkey, &claims.vc)?;
+
+    Ok(vc)
+}
+
+/// `decode_vc_jwt` with the vc_pubkey registered on the parachain.
+pub fn decode_vc_jwt_online<P: ParachainPatch>(api: &P, token: &str) -> ApiResult<Credential> {
+    let vc_pubkey = api.get_vc_pubkey()?;
+    Ok(decode_vc_jwt(token, &vc_pubkey)?)
+}
+
+fn decode_part<T: for<'de> Deserialize<'de>>(part: &str) -> Result<T, JwtError> {
+    serde_json::from_slice(&base64_decode(part)?).map_err(|e| JwtError::Malformed(e.to_string()))
+}
+
+fn base64_encode(data: &[u8]) -> String {
+    base64::encode_config(data, base64::URL_SAFE_NO_PAD)
+}
+
+fn base64_decode(data: &str) -> Result<Vec<u8>, JwtError> {
+    base64::decode_config(data, base64::URL_SAFE_NO_PAD)
+        .map_err(|e| JwtError::Malformed(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vc_management::fixtures::{credential, signed};
+
+    /// The sample credential issued by `pair`, as the worker returns it.
+    fn vc_json(pair: &ed25519::Pair) -> String {
+        let mut vc = credential();
+        vc.issuer.id = hex::encode(pair.public());
+        vc.expiration_timestamp = Some(vc.issuance_timestamp + 86_400_000);
+        signed(serde_json::to_value(vc).unwrap(), pair)
+    }
+
+    /// `token` with its header and claims changed by `edit`, signed again by `pair`.
+    fn resigned<F>(token: &str, pair: &ed25519::Pair, edit: F) -> String
+    where
+        F: FnOnce(&mut Value, &mut Value),
+    {
+        let parts: Vec<&str> = token.split('.').collect();
+        let mut header: Value = decode_part(parts[0]).unwrap();
+        let mut claims: Value = decode_part(parts[1]).unwrap();
+        edit(&mut header, &mut claims);
+
+        let signing_input = format!(
+            "{}.{}",
+            base64_encode(&serde_json::to_vec(&header).unwrap()),
+            base64_encode(&serde_json::to_vec(&claims).unwrap())
+        );
+        let signature = base64_encode(&pair.sign(signing_input.as_bytes()).0);
+        format!("{}.{}", signing_input, signature)
+    }
+
+    #[test]
+    fn vc_jwt_round_trip_works() {
+        let pair = ed25519::Pair::from_seed(&[1u8; 32]);
+        let vc_json = vc_json(&pair);
+
+        let token = encode_vc_jwt(&vc_json, |message| pair.sign(message)).unwrap();
+        assert_eq!(token.split('.').count(), 3);
+
+        // the proof of the worker is kept
+        let decoded = decode_vc_jwt(&token, &pair.public()).unwrap();
+        assert_eq!(decoded, serde_json::from_str(&vc_json).unwrap());
+        assert!(decoded.proof.is_some());
+
+        let other = ed25519::Pair::from_seed(&[2u8; 32]);
+        assert_eq!(
+            decode_vc_jwt(&token, &other.public()),
+            Err(JwtError::BadSignature)
+        );
+    }
+
+    #[test]
+    fn decode_vc_jwt_rejects_tampered_tokens() {
+        let pair = ed25519::Pair::from_seed(&[1u8; 32]);
+        let token = encode_vc_jwt(&vc_json(&pair), |message| pair.sign(message)).unwrap();
+
+        let tampered = resigned(&token, &pair, |_, claims| {
+            claims["sub"] = Value::String("00".repeat(32));
+        });
+        assert_eq!(
+            decode_vc_jwt(&tampered, &pair.public()),
+            Err(JwtError::ClaimMismatch("sub"))
+        );
+
+        let parts: Vec<&str> = tampered.split('.').collect();
+        let unsigned = format!(
+            "{}.{}.{}",
+            parts[0],
+            parts[1],
+            token.split('.').last().unwrap()
+        );
+        assert_eq!(
+            decode_vc_jwt(&unsigned, &pair.public()),
+            Err(JwtError::BadSignature)
+        );
+
+        let tampered = resigned(&token, &pair, |header, _| {
+            header["typ"] = Value::String("at+jwt".to_string());
+        });
+        assert_eq!(
+            decode_vc_jwt(&tampered, &pair.public()),
+            Err(JwtError::UnsupportedType("at+jwt".to_string()))
+        );
+
+        let tampered = resigned(&token, &pair, |header, _| {
+            header["kid"] = Value::String("00".repeat(32));
+        });
+        assert!(matches!(
+            decode_vc_jwt(&tampered, &pair.public()),
+            Err(JwtError::KeyIdMismatch { .. })
+        ));
+
+        let tampered = resigned(&token, &pair, |_, claims| {
+            claims["vc"]["credentialSubject"]["values"] = serde_json::json!([true]);
+        });
+        assert_eq!(
+            decode_vc_jwt(&tampered, &pair.public()),
+            Err(JwtError::Proof(VerificationError::BadSignature))
+        );
+    }
+}
@@ -0,0 +1,249 @@
+use crate::primitives::{
+    params::compare_decimal,
+    vc::{AssertionLogic, Op},
+};
+use chrono::NaiveDate;
+use std::{cmp::Ordering, collections::HashMap, fmt};
+use thiserror::Error;
+
+/**
+ * Evaluation of the `assertions` of a credential subject, so relying parties can re-derive its
+ * `values` from data they fetched themselves.
+ *
+ * An item `{src, op, dst}` compares the value bound to the variable `src` with `dst`, itself a
+ * literal or a `$` variable. Operands are compared as booleans, decimal numbers or
+ * `YYYY-MM-DD` dates when both parse as such, as strings otherwise.
+ */
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum EvalError {
+    #[error("variable {0} is not bound")]
+    Unbound(String),
+    #[error("{left} {op} {right} can not be compared")]
+    Incomparable { left: String, op: Op, right: String },
+}
+
+/// Values of the variables an `AssertionLogic` refers to, e.g. `$total_txs`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Bindings {
+    values: HashMap<String, String>,
+}
+
+impl Bindings {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Bind `variable`, with or without its leading `$`.
+    pub fn bind(mut self, variable: &str, value: impl ToString) -> Self {
+        self.values.insert(
+            variable.trim_start_matches('$').to_string(),
+            value.to_string(),
+        );
+        self
+    }
+
+    pub fn get(&self, variable: &str) -> Option<&str> {
+        self.values
+            .get(variable.trim_start_matches('$'))
+            .map(String::as_str)
+    }
+
+    fn resolve<'a>(&'a self, operand: &'a str) -> Result<&'a str, EvalError> {
+        if operand.starts_with('$') {
+            self.get(operand)
+                .ok_or_else(|| EvalError::Unbound(operand.to_string()))
+        } else {
+            Ok(operand)
+        }
+    }
+}
+
+/// Evaluate `logic`. An empty `and` holds, an empty `or` doesn't.
+pub fn evaluate(logic: &AssertionLogic, bindings: &Bindings) -> Result<bool, EvalError> {
+    match logic {
+        AssertionLogic::Item { src, op, dst } => {
+            let left = bindings.resolve(src)?;
+            let right = bindings.resolve(dst)?;
+            compare(left, *op, right)
+        }
+        AssertionLogic::And { items } => {
+            for item in items {
+                if !evaluate(item, bindings)? {
+                    return Ok(false);
+                }
+            }
+            Ok(true)
+        }
+        AssertionLogic::Or { items } => {
+            for item in items {
+                if evaluate(item, bindings)? {
+                    return Ok(true);
+                }
+            }
+            Ok(false)
+        }
+    }
+}
+
+/// The `values` of a credential subject with `assertions`, one per assertion.
+pub fn evaluate_values(
+    assertions: &[AssertionLogic],
+    bindings: &Bindings,
+) -> Result<Vec<bool>, EvalError> {
+    assertions
+        .iter()
+        .map(|logic| evaluate(logic, bindings))
+        .collect()
+}
+
+fn compare(left: &str, op: Op, right: &str) -> Result<bool, EvalError> {
+    let incomparable = || EvalError::Incomparable {
+        left: left.to_string(),
+        op,
+        right: right.to_string(),
+    };
+
+    let ordering = if let (Ok(left), Ok(right)) = (left.parse::<bool>(), right.parse::<bool>()) {
+        match op {
+            Op::Equal | Op::NotEq => left.cmp(&right),
+            _ => return Err(incomparable()),
+        }
+    } else if let Some(ordering) = compare_decimal(left, right) {
+        ordering
+    } else if let (Ok(left), Ok(right)) = (
+        NaiveDate::parse_from_str(left, "%Y-%m-%d"),
+        NaiveDate::parse_from_str(right, "%Y-%m-%d"),
+    ) {
+        left.cmp(&right)
+    } else {
+        match op {
+            Op::Equal | Op::NotEq => left.cmp(right),
+            _ => return Err(incomparable()),
+        }
+    };
+
+    Ok(match op {
+        Op::GreaterThan => ordering == Ordering::Greater,
+        Op::LessThan => ordering == Ordering::Less,
+        Op::GreaterEq => ordering != Ordering::Less,
+        Op::LessEq => ordering != Ordering::Greater,
+        Op::Equal => ordering == Ordering::Equal,
+        Op::NotEq => ordering != Ordering::Equal,
+    })
+}
+
+impl fmt::Display for Op {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let op = match self {
+            Op::GreaterThan => ">",
+            Op::LessThan => "<",
+            Op::GreaterEq => ">=",
+            Op::LessEq => "<=",
+            Op::Equal => "==",
+            Op::NotEq => "!=",
+        };
+        f.write_str(op)
+    }
+}
+
+/// Written as an expression, e.g. `$total_txs >= 1 && ($network == Litentry || $network == Litmus)`.
+impl fmt::Display for AssertionLogic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AssertionLogic::Item { src, op, dst } => write!(f, "{} {} {}", src, op, dst),
+            AssertionLogic::And { items } => write_group(f, items, "&&", "true"),
+            AssertionLogic::Or { items } => write_group(f, items, "||", "false"),
+        }
+    }
+}
+
+fn write_group(
+    f: &mut fmt::Formatter<'_>,
+    items: &[Box<AssertionLogic>],
+    separator: &str,
+    empty: &str,
+) -> fmt::Result {
+    if items.is_empty() {
+        return f.write_str(empty);
+    }
+
+    for (i, item) in items.iter().enumerate() {
+        if i > 0 {
+            write!(f, " {} ", separator)?;
+        }
+        match item.as_ref() {
+            AssertionLogic::Item { .. } => write!(f, "{}", item)?,
+            _ => write!(f, "({})", item)?,
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vc_management::fixtures::{sample, A1};
+
+    fn assertions(vc_json: &str) -> Vec<AssertionLogic> {
+        sample(vc_json).credential_subject.assertions
+    }
+
+    #[test]
+    fn evaluate_values_rederives_samples() {
+        let a1 = assertions(A1);
+        let bindings = Bindings::new()
+            .bind("$has_web2_account", true)
+            .bind("has_web3_account", false);
+        assert_eq!(evaluate_values(&a1, &bindings), Ok(vec![false]));
+
+        let a8 = assertions(include_str!("../../docs/vc-examples/a8.json"));
+        let bindings = Bindings::new()
+            .bind("$total_txs", 0)
+            .bind("$network", "Litentry");
+        assert_eq!(evaluate_values(&a8, &bindings), Ok(vec![true]));
+        assert_eq!(
+            evaluate_values(&a8, &Bindings::new().bind("$total_txs", 0)),
+            Err(EvalError::Unbound("$network".to_string()))
+        );
+
+        let a4 = assertions(include_str!("../../docs/vc-examples/a4.json"));
+        let bindings = Bindings::new()
+            .bind("$minimum_amount", "1.0010")
+            .bind("$from_date", "2016-12-31")
+            .bind("$to_date", "2023-04-13");
+        assert_eq!(evaluate_values(&a4, &bindings), Ok(vec![true]));
+    }
+
+    #[test]
+    fn compare_works() {
+        assert_eq!(
+            compare("100000000000000000000001", Op::GreaterThan, "999"),
+            Ok(true)
+        );
+        assert_eq!(compare("10", Op::GreaterThan, "9.99"), Ok(true));
+        assert_eq!(compare("-0", Op::Equal, "0.000"), Ok(true));
+        assert_eq!(compare("-2", Op::LessThan, "-1.5"), Ok(true));
+        assert_eq!(compare("2023-04-13", Op::GreaterEq, "2017-01-01"), Ok(true));
+        assert_eq!(compare("Litmus", Op::NotEq, "Litentry"), Ok(true));
+        assert_eq!(
+            compare("Litmus", Op::LessThan, "Litentry"),
+            Err(EvalError::Incomparable {
+                left: "Litmus".to_string(),
+                op: Op::LessThan,
+                right: "Litentry".to_string(),
+            })
+        );
+        assert!(compare("true", Op::GreaterThan, "false").is_err());
+    }
+
+    #[test]
+    fn display_works() {
+        let a8 = assertions(include_str!("../../docs/vc-examples/a8.json"));
+        assert_eq!(
+            a8[0].to_string(),
+            "$total_txs >= 0 && $total_txs < 1 && ($network == Litentry)"
+        );
+        assert_eq!(AssertionLogic::Or { items: vec![] }.to_string(), "false");
+    }
+}
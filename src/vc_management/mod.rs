@@ -1,16 +1,36 @@
 use sp_core::H256;
+use substrate_api_client::api::Result as ApiResult;
 
-use crate::primitives::{assertion::Assertion, MrEnclave};
+use crate::{
+    primitives::{assertion::Assertion, MrEnclave},
+    ExtrinsicOutcome,
+};
+use events::{VCDisabledEvent, VCIssuedEvent, VCRevokedEvent};
 
 pub mod api;
+pub mod canonicalize;
 pub mod events;
+#[cfg(test)]
+pub(crate) mod fixtures;
+pub mod jwt;
+pub mod logic;
+pub mod presentation;
+pub mod schema;
+pub mod store;
+pub mod validity;
 pub mod verify;
 pub mod xtbuilder;
 
 pub const VC_PALLET_NAME: &str = "VCManagement";
 
+/// `request_vc` waits for the TEE worker to issue the VC. A `RequestVCFailed` answer is
+/// returned as an `ApiClientError::Other` wrapping a `RequestFailed`.
 pub trait VcManagementApi {
-    fn request_vc(&self, shard: &MrEnclave, assertion: &Assertion);
-    fn disable_vc(&self, vc_index: &H256);
-    fn revoke_vc(&self, vc_index: &H256);
+    fn request_vc(
+        &self,
+        shard: &MrEnclave,
+        assertion: &Assertion,
+    ) -> ApiResult<ExtrinsicOutcome<VCIssuedEvent>>;
+    fn disable_vc(&self, vc_index: &H256) -> ApiResult<ExtrinsicOutcome<VCDisabledEvent>>;
+    fn revoke_vc(&self, vc_index: &H256) -> ApiResult<ExtrinsicOutcome<VCRevokedEvent>>;
 }
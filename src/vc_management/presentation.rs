@@ -0,0 +1,460 @@
+use crate::{
+    primitives::{enclave::Enclave, AccountId},
+    vc_management::{
+        canonicalize::{canonicalize, Canonicalization},
+        validity::ValidityWindow,
+        verify::{hex_eq, verify_vc_value, VerificationReport},
+    },
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sp_core::{ed25519, sr25519, Pair};
+use thiserror::Error;
+
+/**
+ * A W3C Verifiable Presentation: credentials bundled and signed by their holder.
+ * The holder proof covers `challenge` and `domain`, so a verifier that picks a fresh challenge
+ * can't be answered with a replayed presentation.
+ *
+ * Credentials are issued to Substrate accounts, so holders sign with the sr25519 or ed25519 key
+ * of the subject account. Nothing links an Ethereum key to such an account offline, so Ethereum
+ * holders are not supported.
+ *
+ * https://www.w3.org/TR/vc-data-model/#presentations-0
+ */
+
+pub const PRESENTATION_CONTEXT: &str = "https://www.w3.org/2018/credentials/v1";
+pub const PROOF_PURPOSE: &str = "authentication";
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum PresentationType {
+    VerifiablePresentation,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PresentationProofType {
+    Sr25519Signature2020,
+    Ed25519Signature2020,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Presentation {
+    #[serde(rename = "@context")]
+    pub context: Vec<String>,
+    #[serde(rename = "type")]
+    pub types: Vec<PresentationType>,
+    /// Hex public key of the holder, the subject of the credentials
+    pub holder: String,
+    /// The credentials as the worker issued them, so their proofs still verify
+    pub verifiable_credential: Vec<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proof: Option<PresentationProof>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PresentationProof {
+    #[serde(rename = "type")]
+    pub proof_type: PresentationProofType,
+    pub created_timestamp: u64,
+    pub proof_purpose: String,
+    /// Chosen by the verifier, once per presentation request
+    pub challenge: String,
+    /// The verifier the presentation is meant for
+    pub domain: String,
+    pub verification_method: String,
+    /// Hex signature of the holder, left out of the message it signs
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub proof_value: String,
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum PresentationError {
+    #[error("credential {index} is invalid: {reason}")]
+    InvalidCredential { index: usize, reason: String },
+    #[error("presentation can not be serialized: {0}")]
+    Serialize(String),
+    #[error("proof is missing")]
+    MissingProof,
+    #[error("challenge {found} is not {expected}")]
+    ChallengeMismatch { expected: String, found: String },
+    #[error("domain {found} is not {expected}")]
+    DomainMismatch { expected: String, found: String },
+    #[error("holder {0} does not fit the proof type")]
+    InvalidHolder(String),
+    #[error("verification method {found} is not the holder {expected}")]
+    VerificationMethodMismatch { expected: String, found: String },
+    #[error("proof value is not a hex signature: {0}")]
+    InvalidProofValue(String),
+    #[error("signature does not match the holder")]
+    BadSignature,
+    #[error("credential {index} is issued to {subject}, not the holder")]
+    HolderNotSubject { index: usize, subject: String },
+}
+
+/// The key a holder signs presentations with.
+pub enum HolderPair {
+    Sr25519(sr25519::Pair),
+    Ed25519(ed25519::Pair),
+}
+
+impl HolderPair {
+    /// The `holder` of the presentations this key signs.
+    pub fn holder(&self) -> String {
+        match self {
+            Self::Sr25519(pair) => hex::encode(pair.public()),
+            Self::Ed25519(pair) => hex::encode(pair.public()),
+        }
+    }
+
+    pub fn proof_type(&self) -> PresentationProofType {
+        match self {
+            Self::Sr25519(_) => PresentationProofType::Sr25519Signature2020,
+            Self::Ed25519(_) => PresentationProofType::Ed25519Signature2020,
+        }
+    }
+
+    pub fn sign(&self, message: &[u8]) -> Vec<u8> {
+        match self {
+            Self::Sr25519(pair) => pair.sign(message).0.to_vec(),
+            Self::Ed25519(pair) => pair.sign(message).0.to_vec(),
+        }
+    }
+}
+
+impl From<sr25519::Pair> for HolderPair {
+    fn from(x: sr25519::Pair) -> Self {
+        HolderPair::Sr25519(x)
+    }
+}
+
+impl From<ed25519::Pair> for HolderPair {
+    fn from(x: ed25519::Pair) -> Self {
+        HolderPair::Ed25519(x)
+    }
+}
+
+impl Presentation {
+    /// An unsigned presentation of `credentials`, each the JSON the worker returned.
+    pub fn new(holder: &HolderPair, credentials: &[&str]) -> Result<Self, PresentationError> {
+        let verifiable_credential = credentials
+            .iter()
+            .enumerate()
+            .map(|(index, vc_json)| {
+                serde_json::from_str(vc_json).map_err(|e| PresentationError::InvalidCredential {
+                    index,
+                    reason: e.to_string(),
+                })
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Presentation {
+            context: vec![PRESENTATION_CONTEXT.to_string()],
+            types: vec![PresentationType::VerifiablePresentation],
+            holder: holder.holder(),
+            verifiable_credential,
+            proof: None,
+        })
+    }
+
+    /// Sign the presentation for the verifier `domain`, answering its `challenge`.
+    /// `created_timestamp` is the current unix time in milliseconds.
+    pub fn sign(
+        mut self,
+        holder: &HolderPair,
+        challenge: &str,
+        domain: &str,
+        created_timestamp: u64,
+    ) -> Result<Self, PresentationError> {
+        self.proof = Some(PresentationProof {
+            proof_type: holder.proof_type(),
+            created_timestamp,
+            proof_purpose: PROOF_PURPOSE.to_string(),
+            challenge: challenge.to_string(),
+            domain: domain.to_string(),
+            verification_method: holder.holder(),
+            proof_value: String::new(),
+        });
+
+        let signature = holder.sign(&self.signing_input()?);
+        if let Some(proof) = self.proof.as_mut() {
+            proof.proof_value = hex::encode(signature);
+        }
+
+        Ok(self)
+    }
+
+    /// The message the holder signs: the presentation without `proof.proofValue`, in JCS.
+    pub fn signing_input(&self) -> Result<Vec<u8>, PresentationError> {
+        let mut presentation = self.clone();
+        if let Some(proof) = presentation.proof.as_mut() {
+            proof.proof_value = String::new();
+        }
+        let value = serde_json::to_value(&presentation)
+            .map_err(|e| PresentationError::Serialize(e.to_string()))?;
+
+        Ok(canonicalize(&value, Canonicalization::Jcs).into_bytes())
+    }
+}
+
+/// The outcome of `verify_presentation`: the holder checks and a report per credential.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PresentationReport {
+    pub holder: Vec<PresentationError>,
+    pub credentials: Vec<VerificationReport>,
+}
+
+impl PresentationReport {
+    pub fn is_valid(&self) -> bool {
+        self.holder.is_empty() && self.credentials.iter().all(|report| report.is_valid())
+    }
+}
+
+/// Verify the holder proof of `presentation` against the `challenge` and `domain` the verifier
+/// asked for, and every embedded credential with `verify_vc_value`.
+/// The holder must be the subject of every credential.
+pub fn verify_presentation(
+    presentation: &Presentation,
+    challenge: &str,
+    domain: &str,
+    vc_pubkey: &ed25519::Public,
+    enclaves: &[Enclave<AccountId, String>],
+    validity: &ValidityWindow,
+) -> PresentationReport {
+    let mut holder = verify_presentation_proof(presentation, challenge, domain)
+        .err()
+        .into_iter()
+        .collect::<Vec<_>>();
+
+    let mut credentials = vec![];
+    for (index, value) in presentation.verifiable_credential.iter().enumerate() {
+        let subject_id = value["credentialSubject"]["id"]
+            .as_str()
+            .unwrap_or_default();
+        if !hex_eq(&presentation.holder, subject_id) {
+            holder.push(PresentationError::HolderNotSubject {
+                index,
+                subject: subject_id.to_string(),
+            });
+        }
+
+        let subject = match account_from_hex(subject_id) {
+            Some(subject) => subject,
+            None => {
+                holder.push(PresentationError::InvalidCredential {
+                    index,
+                    reason: format!("subject {}", subject_id),
+                });
+                continue;
+            }
+        };
+
+        // The proof is checked on `value`, the credential as the worker serialized it
+        match verify_vc_value(vc_pubkey, value, &subject, enclaves, validity) {
+            Ok(report) => credentials.push(report),
+            Err(e) => holder.push(PresentationError::InvalidCredential {
+                index,
+                reason: e.to_string(),
+            }),
+        }
+    }
+
+    PresentationReport {
+        holder,
+        credentials,
+    }
+}
+
+/// Verify the holder signature of `presentation`, made for `challenge` and `domain`.
+pub fn verify_presentation_proof(
+    presentation: &Presentation,
+    challenge: &str,
+    domain: &str,
+) -> Result<(), PresentationError> {
+    let proof = presentation
+        .proof
+        .as_ref()
+        .ok_or(PresentationError::MissingProof)?;
+
+    if proof.challenge != challenge {
+        return Err(PresentationError::ChallengeMismatch {
+            expected: challenge.to_string(),
+            found: proof.challenge.clone(),
+        });
+    }
+    if proof.domain != domain {
+        return Err(PresentationError::DomainMismatch {
+            expected: domain.to_string(),
+            found: proof.domain.clone(),
+        });
+    }
+    if !hex_eq(&proof.verification_method, &presentation.holder) {
+        return Err(PresentationError::VerificationMethodMismatch {
+            expected: presentation.holder.clone(),
+            found: proof.verification_method.clone(),
+        });
+    }
+
+    let signature = hex::decode(&proof.proof_value)
+        .map_err(|e| PresentationError::InvalidProofValue(e.to_string()))?;
+    let message = presentation.signing_input()?;
+    let invalid_holder = || PresentationError::InvalidHolder(presentation.holder.clone());
+    let invalid_signature = || PresentationError::InvalidProofValue(hex::encode(&signature));
+
+    let verified = match proof.proof_type {
+        PresentationProofType::Sr25519Signature2020 => {
+            let public = decode_hex_array(&presentation.holder).ok_or_else(invalid_holder)?;
+            let signature =
+                sr25519::Signature::from_slice(&signature).ok_or_else(invalid_signature)?;
+            sr25519::Pair::verify(&signature, &message, &sr25519::Public::from_raw(public))
+        }
+        PresentationProofType::Ed25519Signature2020 => {
+            let public = decode_hex_array(&presentation.holder).ok_or_else(invalid_holder)?;
+            let signature =
+                ed25519::Signature::from_slice(&signature).ok_or_else(invalid_signature)?;
+            ed25519::Pair::verify(&signature, &message, &ed25519::Public::from_raw(public))
+        }
+    };
+
+    if verified {
+        Ok(())
+    } else {
+        Err(PresentationError::BadSignature)
+    }
+}
+
+fn decode_hex_array<const N: usize>(hex_str: &str) -> Option<[u8; N]> {
+    hex::decode(hex_str.trim_start_matches("0x"))
+        .ok()?
+        .try_into()
+        .ok()
+}
+
+fn account_from_hex(hex_str: &str) -> Option<AccountId> {
+    decode_hex_array::<32>(hex_str).map(AccountId::new)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vc_management::fixtures::{credential, enclave, signed, A1 as VC};
+
+    const CHALLENGE: &str = "b2a1e1f0";
+    const DOMAIN: &str = "verifier.litentry.io";
+
+    fn holders() -> Vec<HolderPair> {
+        vec![
+            sr25519::Pair::from_string("//Alice", None).unwrap().into(),
+            ed25519::Pair::from_string("//Alice", None).unwrap().into(),
+        ]
+    }
+
+    /// The A1 sample issued to `holder` by `issuer`, and the enclave holding `issuer`.
+    fn issued_to(
+        holder: &HolderPair,
+        issuer: &ed25519::Pair,
+    ) -> (String, Enclave<AccountId, String>) {
+        let mut vc = credential();
+        vc.credential_subject.id = holder.holder();
+        vc.issuer.id = hex::encode(issuer.public());
+        let enclave = enclave(&vc);
+
+        (signed(serde_json::to_value(vc).unwrap(), issuer), enclave)
+    }
+
+    #[test]
+    fn presentation_proof_works() {
+        for holder in holders() {
+            let presentation = Presentation::new(&holder, &[VC])
+                .unwrap()
+                .sign(&holder, CHALLENGE, DOMAIN, 1)
+                .unwrap();
+
+            assert_eq!(
+                verify_presentation_proof(&presentation, CHALLENGE, DOMAIN),
+                Ok(())
+            );
+            assert!(matches!(
+                verify_presentation_proof(&presentation, "replayed", DOMAIN),
+                Err(PresentationError::ChallengeMismatch { .. })
+            ));
+            assert!(matches!(
+                verify_presentation_proof(&presentation, CHALLENGE, "other.io"),
+                Err(PresentationError::DomainMismatch { .. })
+            ));
+
+            let mut tampered = presentation.clone();
+            tampered.verifiable_credential.pop();
+            assert_eq!(
+                verify_presentation_proof(&tampered, CHALLENGE, DOMAIN),
+                Err(PresentationError::BadSignature)
+            );
+        }
+    }
+
+    #[test]
+    fn presentation_serde_keeps_proof() {
+        let holder = holders().remove(1);
+        let presentation = Presentation::new(&holder, &[VC])
+            .unwrap()
+            .sign(&holder, CHALLENGE, DOMAIN, 1)
+            .unwrap();
+
+        let json = serde_json::to_string(&presentation).unwrap();
+        let presentation: Presentation = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            verify_presentation_proof(&presentation, CHALLENGE, DOMAIN),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn holder_must_be_subject() {
+        // The sample credential is issued to //Alice's sr25519 key
+        for holder in holders().into_iter().skip(1) {
+            let presentation = Presentation::new(&holder, &[VC])
+                .unwrap()
+                .sign(&holder, CHALLENGE, DOMAIN, 1)
+                .unwrap();
+
+            let report = verify_presentation(
+                &presentation,
+                CHALLENGE,
+                DOMAIN,
+                &ed25519::Public::from_raw([0u8; 32]),
+                &[],
+                &ValidityWindow::default(),
+            );
+            assert!(!report.is_valid());
+            assert!(matches!(
+                report.holder.first(),
+                Some(PresentationError::HolderNotSubject { index: 0, .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn verify_presentation_works() {
+        let issuer = ed25519::Pair::from_seed(&[1u8; 32]);
+        for holder in holders() {
+            let (vc_json, enclave) = issued_to(&holder, &issuer);
+            let presentation = Presentation::new(&holder, &[vc_json.as_str()])
+                .unwrap()
+                .sign(&holder, CHALLENGE, DOMAIN, 1)
+                .unwrap();
+
+            let report = verify_presentation(
+                &presentation,
+                CHALLENGE,
+                DOMAIN,
+                &issuer.public(),
+                &[enclave],
+                &ValidityWindow::default(),
+            );
+            assert_eq!(report.holder, vec![]);
+            assert_eq!(report.credentials.len(), 1);
+            assert!(report.is_valid());
+        }
+    }
+}
@@ -0,0 +1,171 @@
+use crate::{
+    primitives::{
+        assertion::Assertion,
+        assertion_info::{
+            A10_INFO, A11_INFO, A1_INFO, A2_INFO, A3_INFO, A4_INFO, A6_INFO, A7_INFO, A8_INFO,
+        },
+    },
+    vc_management::verify::VerificationError,
+};
+use jsonschema::{Draft, JSONSchema};
+use serde_json::Value;
+
+/**
+ * JSON schemas of the `credentialSubject` the worker issues for each assertion, in
+ * `docs/templates/assertions`. They pin `type`, `description`, the shape of the `assertions`
+ * logic and a single value, as seen in `docs/vc-examples`.
+ *
+ * The pinned `type` is the `expected_subject_type` of the assertion registry.
+ *
+ * Assertions without a documented sample get `UNPINNED`, which only checks the shape: their
+ * `type`, `description` and logic are not known to the SDK. So do credentials of an unknown
+ * `credentialSubject.type`.
+ */
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AssertionSchema {
+    /// `credentialSubject.type` of the credentials, `None` when not pinned
+    pub subject_type: Option<&'static str>,
+    pub schema: &'static str,
+}
+
+macro_rules! pinned_schema {
+    ($subject_type:expr, $file:literal) => {
+        AssertionSchema {
+            subject_type: $subject_type,
+            schema: include_str!(concat!("../../docs/templates/assertions/", $file)),
+        }
+    };
+}
+
+pub const A1: AssertionSchema = pinned_schema!(A1_INFO.expected_subject_type, "a1.json");
+pub const A2: AssertionSchema = pinned_schema!(A2_INFO.expected_subject_type, "a2.json");
+pub const A3: AssertionSchema = pinned_schema!(A3_INFO.expected_subject_type, "a3.json");
+pub const A4: AssertionSchema = pinned_schema!(A4_INFO.expected_subject_type, "a4.json");
+/// Requested through the Twitter follow/retweet flow, there is no `Assertion` variant for it.
+pub const A5: AssertionSchema = pinned_schema!(
+    Some("Retweet a tweet as a follower of the tweet author"),
+    "a5.json"
+);
+pub const A6: AssertionSchema = pinned_schema!(A6_INFO.expected_subject_type, "a6.json");
+pub const A7: AssertionSchema = pinned_schema!(A7_INFO.expected_subject_type, "a7.json");
+pub const A8: AssertionSchema = pinned_schema!(A8_INFO.expected_subject_type, "a8.json");
+pub const A10: AssertionSchema = pinned_schema!(A10_INFO.expected_subject_type, "a10.json");
+pub const A11: AssertionSchema = pinned_schema!(A11_INFO.expected_subject_type, "a11.json");
+pub const UNPINNED: AssertionSchema = AssertionSchema {
+    subject_type: None,
+    schema: include_str!("../../docs/templates/assertions/unpinned.json"),
+};
+
+pub const PINNED_SCHEMAS: [AssertionSchema; 10] = [A1, A2, A3, A4, A5, A6, A7, A8, A10, A11];
+
+/// The schema of the credentials issued for `assertion`.
+pub fn schema_for_assertion(assertion: &Assertion) -> AssertionSchema {
+    match assertion {
+        Assertion::A1 => A1,
+        Assertion::A2(..) => A2,
+        Assertion::A3(..) => A3,
+        Assertion::A4(..) => A4,
+        Assertion::A6 => A6,
+        Assertion::A7(..) => A7,
+        Assertion::A8(..) => A8,
+        Assertion::A10(..) => A10,
+        Assertion::A11(..) => A11,
+        // no sample documents their subjects yet, see `AssertionInfo::expected_subject_type`
+        Assertion::A9 | Assertion::A13(..) | Assertion::A14 | Assertion::Achainable(..) => UNPINNED,
+    }
+}
+
+/// The pinned schema of credentials with `credentialSubject.type` `subject_type`.
+pub fn schema_for_subject_type(subject_type: &str) -> Option<AssertionSchema> {
+    PINNED_SCHEMAS
+        .into_iter()
+        .find(|schema| schema.subject_type == Some(subject_type))
+}
+
+/// The schema `credentialSubject` is validated against, `UNPINNED` for unknown types.
+pub fn select_schema(subject_type: &str) -> AssertionSchema {
+    schema_for_subject_type(subject_type).unwrap_or(UNPINNED)
+}
+
+/// Validate `value` against the JSON schema `schema`.
+pub fn validate(schema: &str, value: &Value) -> Vec<VerificationError> {
+    let compiled_schema = serde_json::from_str(schema)
+        .map_err(|e| e.to_string())
+        .and_then(|schema: Value| {
+            JSONSchema::options()
+                .with_draft(Draft::Draft202012)
+                .compile(&schema)
+                .map_err(|e| e.to_string())
+        });
+    let compiled_schema = match compiled_schema {
+        Ok(compiled_schema) => compiled_schema,
+        Err(e) => return vec![VerificationError::Schema(e)],
+    };
+
+    let errors = match compiled_schema.validate(value) {
+        Ok(()) => vec![],
+        Err(errors) => errors
+            .map(|e| VerificationError::Schema(e.to_string()))
+            .collect(),
+    };
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLES: [(&str, AssertionSchema); 10] = [
+        (include_str!("../../docs/vc-examples/a1.json"), A1),
+        (include_str!("../../docs/vc-examples/a2.json"), A2),
+        (include_str!("../../docs/vc-examples/a3.json"), A3),
+        (include_str!("../../docs/vc-examples/a4.json"), A4),
+        (include_str!("../../docs/vc-examples/a5.json"), A5),
+        (include_str!("../../docs/vc-examples/a6.json"), A6),
+        (include_str!("../../docs/vc-examples/a7.json"), A7),
+        (include_str!("../../docs/vc-examples/a8.json"), A8),
+        (include_str!("../../docs/vc-examples/a10.json"), A10),
+        (include_str!("../../docs/vc-examples/a11.json"), A11),
+    ];
+
+    fn subject(vc_json: &str) -> Value {
+        let vc: Value = serde_json::from_str(vc_json).unwrap();
+        vc["credentialSubject"].clone()
+    }
+
+    #[test]
+    fn samples_select_and_match_their_schema() {
+        for (vc_json, expected) in SAMPLES {
+            let subject = subject(vc_json);
+            let schema = schema_for_subject_type(subject["type"].as_str().unwrap()).unwrap();
+
+            assert_eq!(schema, expected);
+            assert_eq!(validate(schema.schema, &subject), vec![]);
+            assert_eq!(validate(UNPINNED.schema, &subject), vec![]);
+        }
+    }
+
+    #[test]
+    fn schemas_reject_other_credentials() {
+        let a1 = subject(SAMPLES[0].0);
+        for (_, schema) in &SAMPLES[1..] {
+            assert!(!validate(schema.schema, &a1).is_empty());
+        }
+
+        let mut a6 = subject(SAMPLES[5].0);
+        a6["values"] = serde_json::json!([true, false]);
+        assert!(!validate(A6.schema, &a6).is_empty());
+    }
+
+    #[test]
+    fn unknown_subject_types_are_still_validated() {
+        let mut a1 = subject(SAMPLES[0].0);
+        a1["type"] = serde_json::json!("Tampered");
+        assert_eq!(select_schema("Tampered"), UNPINNED);
+        assert_eq!(validate(select_schema("Tampered").schema, &a1), vec![]);
+
+        a1["values"] = serde_json::json!([]);
+        assert!(!validate(select_schema("Tampered").schema, &a1).is_empty());
+    }
+}
@@ -0,0 +1,292 @@
+use crate::{
+    api_client_patch::parachain::ParachainPatch,
+    primitives::{
+        assertion::Assertion,
+        crypto::AesOutput,
+        network::Web3Network,
+        vc::{Credential, Status},
+        VCIndex,
+    },
+    utils::crypto::decrypt_vc_with_user_shielding_key,
+    vc_management::events::VCIssuedEvent,
+};
+use codec::{Decode, Encode};
+use std::{
+    fs,
+    mem::{discriminant, Discriminant},
+    path::PathBuf,
+};
+use substrate_api_client::{api::Error as ApiClientError, api::Result as ApiResult};
+use thiserror::Error;
+
+/// An issued credential as it is kept at rest: still encrypted with the user shielding key the
+/// worker used, next to what the parachain knows about it.
+#[derive(Encode, Decode, Clone, Debug, PartialEq, Eq)]
+pub struct StoredCredential {
+    pub index: VCIndex,
+    pub assertion: Assertion,
+    pub vc: AesOutput,
+    /// Status in `VCRegistry` at the last resync, `None` once the credential was revoked.
+    pub status: Option<Status>,
+}
+
+impl StoredCredential {
+    pub fn decrypt(&self, user_shielding_key: &[u8]) -> Result<Credential, String> {
+        decrypt_vc_with_user_shielding_key(user_shielding_key, self.vc.clone())
+    }
+}
+
+impl From<VCIssuedEvent> for StoredCredential {
+    fn from(event: VCIssuedEvent) -> Self {
+        StoredCredential {
+            index: event.index,
+            assertion: event.assertion,
+            vc: event.vc,
+            status: Some(Status::Active),
+        }
+    }
+}
+
+/// Which credentials to list, every criterion left empty matches all credentials.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CredentialFilter {
+    pub assertion: Option<Discriminant<Assertion>>,
+    pub network: Option<Web3Network>,
+    pub status: Option<Option<Status>>,
+}
+
+impl CredentialFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Credentials of the same assertion type as `assertion`, whatever its parameters.
+    pub fn assertion(mut self, assertion: &Assertion) -> Self {
+        self.assertion = Some(discriminant(assertion));
+        self
+    }
+
+    /// Credentials whose assertion supports `network`.
+    pub fn network(mut self, network: Web3Network) -> Self {
+        self.network = Some(network);
+        self
+    }
+
+    /// Credentials with `status`, `None` for revoked ones.
+    pub fn status(mut self, status: Option<Status>) -> Self {
+        self.status = Some(status);
+        self
+    }
+
+    pub fn matches(&self, credential: &StoredCredential) -> bool {
+        let assertion_matches = match self.assertion {
+            Some(assertion) => discriminant(&credential.assertion) == assertion,
+            None => true,
+        };
+        let network_matches = match self.network {
+            Some(network) => credential
+                .assertion
+                .get_supported_web3networks()
+                .map_or(false, |networks| networks.contains(&network)),
+            None => true,
+        };
+        let status_matches = match &self.status {
+            Some(status) => &credential.status == status,
+            None => true,
+        };
+
+        assertion_matches && network_matches && status_matches
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum StoreError {
+    #[error("credential store io: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("credential store codec: {0}")]
+    Codec(#[from] codec::Error),
+}
+
+impl From<StoreError> for ApiClientError {
+    fn from(error: StoreError) -> Self {
+        ApiClientError::Other(Box::new(error))
+    }
+}
+
+pub type StoreResult<T> = Result<T, StoreError>;
+
+/// Issued credentials of one user, keyed by their `VCIndex`.
+pub trait CredentialStore {
+    /// Insert `credential`, replacing the one stored under the same index.
+    fn insert(&mut self, credential: StoredCredential) -> StoreResult<()>;
+    fn get(&self, index: &VCIndex) -> StoreResult<Option<StoredCredential>>;
+    fn remove(&mut self, index: &VCIndex) -> StoreResult<Option<StoredCredential>>;
+    /// The credentials `filter` matches, ordered by index.
+    fn list(&self, filter: &CredentialFilter) -> StoreResult<Vec<StoredCredential>>;
+
+    /// Refresh the status of every stored credential from `VCRegistry`.
+    /// Returns the number of credentials whose status changed.
+    fn resync<P: ParachainPatch>(&mut self, api: &P) -> ApiResult<usize>
+    where
+        Self: Sized,
+    {
+        let mut changed = 0;
+        for mut credential in self.list(&CredentialFilter::new())? {
+            let status = api
+                .get_vc_context(&credential.index)?
+                .map(|context| context.status);
+            if status != credential.status {
+                credential.status = status;
+                self.insert(credential)?;
+                changed += 1;
+            }
+        }
+
+        Ok(changed)
+    }
+}
+
+/// A `CredentialStore` keeping each credential SCALE encoded in `<dir>/<index>.vc`.
+#[derive(Debug, Clone)]
+pub struct FileCredentialStore {
+    dir: PathBuf,
+}
+
+impl FileCredentialStore {
+    pub const EXTENSION: &'static str = "vc";
+
+    /// Open the store in `dir`, creating the directory when it does not exist.
+    pub fn open(dir: impl Into<PathBuf>) -> StoreResult<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)?;
+        Ok(FileCredentialStore { dir })
+    }
+
+    fn path(&self, index: &VCIndex) -> PathBuf {
+        self.dir
+            .join(format!("{}.{}", hex::encode(index), Self::EXTENSION))
+    }
+
+    fn read(path: &PathBuf) -> StoreResult<StoredCredential> {
+        let encoded = fs::read(path)?;
+        Ok(StoredCredential::decode(&mut encoded.as_slice())?)
+    }
+}
+
+impl CredentialStore for FileCredentialStore {
+    fn insert(&mut self, credential: StoredCredential) -> StoreResult<()> {
+        // Written aside first, so a crash never leaves a truncated credential behind.
+        let path = self.path(&credential.index);
+        let tmp_path = path.with_extension("tmp");
+        fs::write(&tmp_path, credential.encode())?;
+        fs::rename(tmp_path, path)?;
+        Ok(())
+    }
+
+    fn get(&self, index: &VCIndex) -> StoreResult<Option<StoredCredential>> {
+        let path = self.path(index);
+        if !path.exists() {
+            return Ok(None);
+        }
+        Self::read(&path).map(Some)
+    }
+
+    fn remove(&mut self, index: &VCIndex) -> StoreResult<Option<StoredCredential>> {
+        let credential = self.get(index)?;
+        if credential.is_some() {
+            fs::remove_file(self.path(index))?;
+        }
+        Ok(credential)
+    }
+
+    fn list(&self, filter: &CredentialFilter) -> StoreResult<Vec<StoredCredential>> {
+        let mut credentials = vec![];
+        for entry in fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|extension| extension.to_str()) != Some(Self::EXTENSION) {
+                continue;
+            }
+
+            let credential = Self::read(&path)?;
+            if filter.matches(&credential) {
+                credentials.push(credential);
+            }
+        }
+        credentials.sort_by_key(|credential| credential.index);
+
+        Ok(credentials)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::primitives::assertion::ParameterString;
+
+    fn stored(index: u8, assertion: Assertion, status: Option<Status>) -> StoredCredential {
+        StoredCredential {
+            index: VCIndex::repeat_byte(index),
+            assertion,
+            vc: AesOutput::default(),
+            status,
+        }
+    }
+
+    #[test]
+    fn credential_filter_matches_works() {
+        let a1 = stored(1, Assertion::A1, Some(Status::Active));
+        let a7 = stored(2, Assertion::A7(ParameterString::default()), None);
+
+        assert!(CredentialFilter::new().matches(&a1));
+        assert!(CredentialFilter::new()
+            .assertion(&Assertion::A7(ParameterString::truncate_from(
+                b"10".to_vec()
+            )))
+            .matches(&a7));
+        assert!(!CredentialFilter::new()
+            .assertion(&Assertion::A7(ParameterString::default()))
+            .matches(&a1));
+        assert!(CredentialFilter::new()
+            .network(Web3Network::Polkadot)
+            .matches(&a7));
+        assert!(!CredentialFilter::new()
+            .network(Web3Network::Polkadot)
+            .matches(&a1));
+        assert!(CredentialFilter::new().status(None).matches(&a7));
+        assert!(!CredentialFilter::new()
+            .status(Some(Status::Active))
+            .matches(&a7));
+    }
+
+    #[test]
+    fn file_credential_store_works() {
+        let dir = std::env::temp_dir().join(format!(
+            "vc-store-{}",
+            hex::encode(rand::random::<[u8; 8]>())
+        ));
+        let mut store = FileCredentialStore::open(&dir).unwrap();
+
+        let a1 = stored(2, Assertion::A1, Some(Status::Active));
+        let a6 = stored(1, Assertion::A6, Some(Status::Disabled));
+        store.insert(a1.clone()).unwrap();
+        store.insert(a6.clone()).unwrap();
+
+        assert_eq!(store.get(&a1.index).unwrap(), Some(a1.clone()));
+        assert_eq!(
+            store.list(&CredentialFilter::new()).unwrap(),
+            vec![a6.clone(), a1.clone()]
+        );
+        assert_eq!(
+            store
+                .list(&CredentialFilter::new().assertion(&Assertion::A1))
+                .unwrap(),
+            vec![a1.clone()]
+        );
+
+        assert_eq!(store.remove(&a6.index).unwrap(), Some(a6.clone()));
+        assert_eq!(store.get(&a6.index).unwrap(), None);
+        assert_eq!(store.list(&CredentialFilter::new()).unwrap(), vec![a1]);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,228 @@
+use crate::{primitives::vc::Credential, vc_management::verify::VerificationError};
+use std::{sync::Arc, time::Duration};
+
+/// Clock skew tolerated between us and the issuing worker by default.
+pub const DEFAULT_CLOCK_SKEW: Duration = Duration::from_secs(60);
+
+/// Where validity checks take the current time from, as unix time in milliseconds.
+pub trait Clock: Send + Sync {
+    fn now(&self) -> u64;
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> u64 {
+        chrono::Utc::now().timestamp_millis() as u64
+    }
+}
+
+/// A clock stopped at the given unix time in milliseconds.
+#[derive(Debug, Clone, Copy)]
+pub struct FixedClock(pub u64);
+
+impl Clock for FixedClock {
+    fn now(&self) -> u64 {
+        self.0
+    }
+}
+
+/// When a credential is valid: from its issuance to its expiration, both widened by `skew`.
+#[derive(Clone)]
+pub struct ValidityWindow {
+    pub clock: Arc<dyn Clock>,
+    pub skew: Duration,
+}
+
+impl Default for ValidityWindow {
+    fn default() -> Self {
+        ValidityWindow {
+            clock: Arc::new(SystemClock),
+            skew: DEFAULT_CLOCK_SKEW,
+        }
+    }
+}
+
+impl std::fmt::Debug for ValidityWindow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ValidityWindow")
+            .field("now", &self.now())
+            .field("skew", &self.skew)
+            .finish()
+    }
+}
+
+impl ValidityWindow {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Arc::new(clock);
+        self
+    }
+
+    pub fn with_skew(mut self, skew: Duration) -> Self {
+        self.skew = skew;
+        self
+    }
+
+    pub fn now(&self) -> u64 {
+        self.clock.now()
+    }
+
+    fn skew_millis(&self) -> u64 {
+        self.skew.as_millis() as u64
+    }
+}
+
+/// Check that `vc` is valid now: issued, not yet expired, and proven no earlier than issued.
+/// A credential without expiration never expires.
+pub fn verify_vc_validity(vc: &Credential, window: &ValidityWindow) -> Vec<VerificationError> {
+    let now = window.now();
+    let skew = window.skew_millis();
+    let issuance_timestamp = vc.issuance_timestamp;
+
+    let mut errors = vec![];
+    if issuance_timestamp > now.saturating_add(skew) {
+        errors.push(VerificationError::NotYetValid {
+            issuance_timestamp,
+            now,
+        });
+    }
+
+    if let Some(expiration_timestamp) = vc.expiration_timestamp {
+        if expiration_timestamp < issuance_timestamp {
+            errors.push(VerificationError::ExpiresBeforeIssuance {
+                issuance_timestamp,
+                expiration_timestamp,
+            });
+        } else if expiration_timestamp.saturating_add(skew) < now {
+            errors.push(VerificationError::Expired {
+                expiration_timestamp,
+                now,
+            });
+        }
+    }
+
+    if let Some(proof) = &vc.proof {
+        if proof.created_timestamp.saturating_add(skew) < issuance_timestamp {
+            errors.push(VerificationError::ProofCreatedBeforeIssuance {
+                created_timestamp: proof.created_timestamp,
+                issuance_timestamp,
+            });
+        }
+    }
+
+    errors
+}
+
+/// Whether `vc` expires within `horizon` from now, already expired credentials included.
+pub fn expires_within(vc: &Credential, horizon: Duration, window: &ValidityWindow) -> bool {
+    let deadline = window.now().saturating_add(horizon.as_millis() as u64);
+    matches!(vc.expiration_timestamp, Some(expiration_timestamp) if expiration_timestamp <= deadline)
+}
+
+/// The credentials that expire within `horizon` from now and should be issued again,
+/// soonest first.
+pub fn expiring_credentials<'a>(
+    credentials: impl IntoIterator<Item = &'a Credential>,
+    horizon: Duration,
+    window: &ValidityWindow,
+) -> Vec<&'a Credential> {
+    let mut expiring: Vec<_> = credentials
+        .into_iter()
+        .filter(|vc| expires_within(vc, horizon, window))
+        .collect();
+    expiring.sort_by_key(|vc| vc.expiration_timestamp);
+    expiring
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vc_management::fixtures;
+
+    const HOUR: u64 = 3_600_000;
+
+    /// The A1 sample, issued at `issuance_timestamp`.
+    fn credential(issuance_timestamp: u64, expiration_timestamp: Option<u64>) -> Credential {
+        let mut vc = fixtures::credential();
+        vc.issuance_timestamp = issuance_timestamp;
+        vc.expiration_timestamp = expiration_timestamp;
+        if let Some(proof) = vc.proof.as_mut() {
+            proof.created_timestamp = issuance_timestamp;
+        }
+        vc
+    }
+
+    fn window(now: u64) -> ValidityWindow {
+        ValidityWindow::new()
+            .with_clock(FixedClock(now))
+            .with_skew(Duration::from_millis(1000))
+    }
+
+    #[test]
+    fn verify_vc_validity_works() {
+        let vc = credential(10 * HOUR, Some(20 * HOUR));
+        assert!(verify_vc_validity(&vc, &window(15 * HOUR)).is_empty());
+        // within the skew
+        assert!(verify_vc_validity(&vc, &window(10 * HOUR - 1000)).is_empty());
+        assert!(verify_vc_validity(&vc, &window(20 * HOUR + 1000)).is_empty());
+
+        assert_eq!(
+            verify_vc_validity(&vc, &window(10 * HOUR - 1001)),
+            vec![VerificationError::NotYetValid {
+                issuance_timestamp: 10 * HOUR,
+                now: 10 * HOUR - 1001,
+            }]
+        );
+        assert_eq!(
+            verify_vc_validity(&vc, &window(20 * HOUR + 1001)),
+            vec![VerificationError::Expired {
+                expiration_timestamp: 20 * HOUR,
+                now: 20 * HOUR + 1001,
+            }]
+        );
+
+        let mut vc = credential(10 * HOUR, Some(5 * HOUR));
+        vc.proof.as_mut().unwrap().created_timestamp = HOUR;
+        assert_eq!(
+            verify_vc_validity(&vc, &window(15 * HOUR)),
+            vec![
+                VerificationError::ExpiresBeforeIssuance {
+                    issuance_timestamp: 10 * HOUR,
+                    expiration_timestamp: 5 * HOUR,
+                },
+                VerificationError::ProofCreatedBeforeIssuance {
+                    created_timestamp: HOUR,
+                    issuance_timestamp: 10 * HOUR,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn expiring_credentials_works() {
+        let never = credential(0, None);
+        let expired = credential(0, Some(HOUR));
+        let soon = credential(0, Some(3 * HOUR));
+        let later = credential(0, Some(30 * HOUR));
+        let window = window(2 * HOUR);
+
+        assert!(!expires_within(
+            &never,
+            Duration::from_secs(86_400),
+            &window
+        ));
+        assert_eq!(
+            expiring_credentials(
+                [&later, &soon, &never, &expired],
+                Duration::from_secs(86_400),
+                &window
+            ),
+            vec![&expired, &soon]
+        );
+    }
+}
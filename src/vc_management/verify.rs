@@ -1,9 +1,29 @@
-use crate::primitives::vc::{Credential, CredentialType};
-use jsonschema::{Draft, JSONSchema};
+use crate::{
+    api_client_patch::parachain::ParachainPatch,
+    primitives::{
+        assertion::{Assertion, ParameterString},
+        assertion_info::ASSERTION_INFOS,
+        enclave::Enclave,
+        params::compare_decimal,
+        vc::{AssertionLogic, Credential, CredentialType, Op, Status, VCContext},
+        AccountId,
+    },
+    utils::enclave::mrenclave_to_bs58,
+    vc_management::{
+        canonicalize::{signing_input, Canonicalization},
+        schema::{schema_for_subject_type, select_schema, validate},
+        validity::{verify_vc_validity, ValidityWindow},
+    },
+};
+use serde_json::Value;
 use sp_core::{
+    blake2_256,
     ed25519::{self, Pair as Ed25519Pair},
-    Pair,
+    Pair, H256,
 };
+use std::{cmp::Ordering, str::FromStr};
+use substrate_api_client::{api::Error as ApiClientError, api::Result as ApiResult};
+use thiserror::Error;
 
 /**
  * Here are the key points that need to be verified for VC, and a large number of verifiable tests need to be conducted for VC.
@@ -17,84 +37,883 @@ const CONTEXT: [&str; 2] = [
     "https://w3id.org/security/suites/ed25519-2020/v1",
 ];
 
-pub fn verify_vc(vc_pubkey: &ed25519::Public, vc: &Credential) -> Result<bool, String> {
-    let verified_schema = verify_vc_schema(vc)?;
-    println!("Verify scheme: {verified_schema}");
-    if !verified_schema {
-        return Ok(verified_schema);
+/// A subject check that did not pass.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum SubjectFailure {
+    /// `credential_subject.id` is not the hex public key of the requesting account.
+    #[error("subject id {found} is not {expected}")]
+    IdMismatch { expected: String, found: String },
+    /// Every assertion needs exactly one value.
+    #[error("{values} values for {assertions} assertions")]
+    ValuesMismatch { assertions: usize, values: usize },
+}
+
+/// An issuer check that did not pass.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum IssuerFailure {
+    /// `issuer.mrenclave` is not the mrenclave of any registered enclave.
+    #[error("mrenclave {0} is not registered")]
+    UnknownMrenclave(String),
+    /// The issuing enclave has no vc_pubkey registered.
+    #[error("issuing enclave has no vc_pubkey")]
+    MissingVcPubkey,
+    /// `issuer.id` is not the vc_pubkey of the issuing enclave.
+    #[error("issuer id {found} is not the enclave vc_pubkey {expected}")]
+    IdMismatch { expected: String, found: String },
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum VerificationError {
+    #[error("schema: {0}")]
+    Schema(String),
+    #[error("unexpected @context {0:?}")]
+    InvalidContext(Vec<String>),
+    #[error("type is not VerifiableCredential")]
+    InvalidType,
+    #[error("subject: {0}")]
+    Subject(#[from] SubjectFailure),
+    #[error("issuer: {0}")]
+    Issuer(#[from] IssuerFailure),
+    #[error("proof is missing")]
+    MissingProof,
+    #[error("proof value is not a hex ed25519 signature: {0}")]
+    InvalidProofValue(String),
+    #[error("credential can not be serialized: {0}")]
+    Serialize(String),
+    #[error("signature does not match the vc_pubkey")]
+    BadSignature,
+    #[error("expired at {expiration_timestamp}, now is {now}")]
+    Expired { expiration_timestamp: u64, now: u64 },
+    #[error("issued at {issuance_timestamp}, after now {now}")]
+    NotYetValid { issuance_timestamp: u64, now: u64 },
+    #[error("expires at {expiration_timestamp}, before its issuance at {issuance_timestamp}")]
+    ExpiresBeforeIssuance {
+        issuance_timestamp: u64,
+        expiration_timestamp: u64,
+    },
+    #[error("proof created at {created_timestamp}, before issuance at {issuance_timestamp}")]
+    ProofCreatedBeforeIssuance {
+        created_timestamp: u64,
+        issuance_timestamp: u64,
+    },
+    #[error("credential id {0} is not a VC index")]
+    InvalidId(String),
+    #[error("credential is not in VCRegistry, it was revoked or never issued")]
+    NotRegistered,
+    #[error("credential is disabled")]
+    Disabled,
+    #[error("credential hash {found:?} is not the registered {expected:?}")]
+    HashMismatch { expected: H256, found: H256 },
+    #[error("registered subject {expected} is not the credential subject {found}")]
+    RegisteredSubjectMismatch { expected: String, found: String },
+    #[error("registered assertion {assertion} is not a {found} credential")]
+    AssertionMismatch { assertion: String, found: String },
+    #[error("registered {param} {expected:?} is not the credential {found:?}")]
+    AssertionParamMismatch {
+        param: &'static str,
+        expected: Vec<String>,
+        found: Vec<String>,
+    },
+}
+
+/// A check the SDK can not make, for lack of knowledge about the credentials of an assertion.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum Unchecked {
+    /// The `credentialSubject.type` of the registered assertion is not known, and the credential
+    /// is not of another known assertion.
+    #[error("the type of {assertion} credentials is unknown, {found} can not be checked")]
+    AssertionKind { assertion: String, found: String },
+    /// How the parameters of the registered assertion appear in the credential logic is not known.
+    #[error("the parameters of {0} can not be checked in the credential logic")]
+    AssertionParams(String),
+}
+
+impl From<VerificationError> for ApiClientError {
+    fn from(error: VerificationError) -> Self {
+        ApiClientError::Other(Box::new(error))
+    }
+}
+
+/// The stages a credential goes through in `verify_vc`, in order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum VerificationStage {
+    Schema,
+    ContextAndType,
+    Subject,
+    Issuer,
+    ProofSignature,
+    Validity,
+    OnChainStatus,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StageOutcome {
+    Passed,
+    Failed(Vec<VerificationError>),
+    /// The stage needs data that was not available, e.g. on-chain status in offline mode.
+    Skipped,
+    /// Nothing failed, but part of the stage could not be checked.
+    Inconclusive(Vec<Unchecked>),
+}
+
+impl StageOutcome {
+    fn from_errors(errors: Vec<VerificationError>) -> Self {
+        Self::from_checks(errors, vec![])
     }
 
-    let verified_vc_info = verify_vc_info(vc);
-    println!("Verify scheme: {verified_vc_info}");
+    fn from_checks(errors: Vec<VerificationError>, unchecked: Vec<Unchecked>) -> Self {
+        if !errors.is_empty() {
+            StageOutcome::Failed(errors)
+        } else if !unchecked.is_empty() {
+            StageOutcome::Inconclusive(unchecked)
+        } else {
+            StageOutcome::Passed
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerificationReport {
+    pub stages: Vec<(VerificationStage, StageOutcome)>,
+}
+
+impl VerificationReport {
+    /// No stage failed. Skipped and inconclusive stages don't count as failures, see
+    /// `is_conclusive`.
+    pub fn is_valid(&self) -> bool {
+        self.stages
+            .iter()
+            .all(|(_, outcome)| !matches!(outcome, StageOutcome::Failed(_)))
+    }
+
+    /// Every stage was checked in full: none was skipped or inconclusive.
+    pub fn is_conclusive(&self) -> bool {
+        self.stages.iter().all(|(_, outcome)| {
+            !matches!(
+                outcome,
+                StageOutcome::Skipped | StageOutcome::Inconclusive(_)
+            )
+        })
+    }
 
-    let verified_subject = verify_vc_subject(vc);
-    println!("Verify scheme: {verified_subject}");
+    pub fn outcome(&self, stage: VerificationStage) -> Option<&StageOutcome> {
+        self.stages
+            .iter()
+            .find(|(s, _)| *s == stage)
+            .map(|(_, outcome)| outcome)
+    }
+
+    pub fn errors(&self) -> impl Iterator<Item = (VerificationStage, &VerificationError)> {
+        self.stages
+            .iter()
+            .flat_map(|(stage, outcome)| match outcome {
+                StageOutcome::Failed(errors) => errors.iter().map(|e| (*stage, e)).collect(),
+                _ => vec![],
+            })
+    }
+}
 
-    let verified_issuer = verify_vc_issuer(vc);
-    println!("Verify scheme: {verified_issuer}");
+/// Verify `vc_json`, the credential as returned by the worker, offline, as issued to `subject` by
+/// one of the registered `enclaves`. The validity stage checks it against `validity`. The
+/// on-chain status is `Skipped`, see `verify_vc_online`.
+///
+/// Fails when `vc_json` is not a `Credential` at all.
+pub fn verify_vc(
+    vc_pubkey: &ed25519::Public,
+    vc_json: &str,
+    subject: &AccountId,
+    enclaves: &[Enclave<AccountId, String>],
+    validity: &ValidityWindow,
+) -> Result<VerificationReport, VerificationError> {
+    let value: Value =
+        serde_json::from_str(vc_json).map_err(|e| VerificationError::Serialize(e.to_string()))?;
+    verify_vc_value(vc_pubkey, &value, subject, enclaves, validity)
+}
+
+/// Same as `verify_vc`, for a credential already parsed into a JSON `value` in document order.
+pub fn verify_vc_value(
+    vc_pubkey: &ed25519::Public,
+    value: &Value,
+    subject: &AccountId,
+    enclaves: &[Enclave<AccountId, String>],
+    validity: &ValidityWindow,
+) -> Result<VerificationReport, VerificationError> {
+    let credential: Credential = serde_json::from_value(value.clone())
+        .map_err(|e| VerificationError::Serialize(e.to_string()))?;
+    let vc = &credential;
 
-    let verified_proof = verify_vc_proof(vc_pubkey, vc)?;
-    println!("Verify scheme: {verified_proof}");
+    let subject_errors = verify_vc_subject(vc, subject)
+        .into_iter()
+        .map(VerificationError::from)
+        .collect();
+    let issuer_errors = verify_vc_issuer(vc, enclaves)
+        .into_iter()
+        .map(VerificationError::from)
+        .collect();
+    let proof_errors = verify_vc_value_proof(vc_pubkey, value)
+        .err()
+        .into_iter()
+        .collect();
 
-    Ok(
-        verified_schema
-            && verified_vc_info
-            && verified_subject
-            && verified_issuer
-            && verified_proof,
-    )
+    Ok(VerificationReport {
+        stages: vec![
+            (
+                VerificationStage::Schema,
+                StageOutcome::from_errors(verify_vc_schema(vc)),
+            ),
+            (
+                VerificationStage::ContextAndType,
+                StageOutcome::from_errors(verify_vc_info(vc)),
+            ),
+            (
+                VerificationStage::Subject,
+                StageOutcome::from_errors(subject_errors),
+            ),
+            (
+                VerificationStage::Issuer,
+                StageOutcome::from_errors(issuer_errors),
+            ),
+            (
+                VerificationStage::ProofSignature,
+                StageOutcome::from_errors(proof_errors),
+            ),
+            (
+                VerificationStage::Validity,
+                StageOutcome::from_errors(verify_vc_validity(vc, validity)),
+            ),
+            (VerificationStage::OnChainStatus, StageOutcome::Skipped),
+        ],
+    })
 }
 
-pub fn verify_vc_schema(vc: &Credential) -> Result<bool, String> {
-    let vc: serde_json::Value = serde_json::to_value(vc).map_err(|e| format!("{:?}", e))?;
-    let schema = include_bytes!("../../docs/templates/vc_schema.json");
-    let schema: serde_json::Value =
-        serde_json::from_slice(schema).map_err(|e| format!("{:?}", e))?;
-    let compiled_schema = JSONSchema::options()
-        .with_draft(Draft::Draft202012)
-        .compile(&schema)
-        .map_err(|e| format!("{:?}", e))?;
+/// Verify `vc_json`, the credential as returned by the worker, as issued to `subject`.
+/// Besides the offline stages of `verify_vc`, its proof included, the credential is checked
+/// against its `VCRegistry` entry, see `verify_vc_registry`. The enclaves and vc_pubkey are read
+/// from the parachain.
+pub fn verify_vc_online<P: ParachainPatch>(
+    api: &P,
+    vc_json: &str,
+    subject: &AccountId,
+    validity: &ValidityWindow,
+) -> ApiResult<VerificationReport> {
+    let vc: Credential =
+        serde_json::from_str(vc_json).map_err(|e| VerificationError::Serialize(e.to_string()))?;
+    let vc_pubkey = api.get_vc_pubkey()?;
+    let enclaves = api.enclaves()?;
+
+    let mut report = verify_vc(&vc_pubkey, vc_json, subject, &enclaves, validity)?;
+    let registry_outcome = match H256::from_str(&vc.id) {
+        Ok(vc_index) => {
+            let context = api.get_vc_context(&vc_index)?;
+            verify_vc_registry(vc_json, &vc, context.as_ref())
+        }
+        Err(_) => StageOutcome::Failed(vec![VerificationError::InvalidId(vc.id.clone())]),
+    };
+    for (stage, outcome) in report.stages.iter_mut() {
+        if *stage == VerificationStage::OnChainStatus {
+            *outcome = registry_outcome.clone();
+        }
+    }
 
-    Ok(compiled_schema.is_valid(&vc))
+    Ok(report)
 }
 
-pub fn verify_vc_info(vc: &Credential) -> bool {
+/// Validate `vc` against the generic credential schema, and its `credentialSubject` against the
+/// schema of its assertion, selected by `credentialSubject.type`. Subjects of an unknown type are
+/// validated against `UNPINNED`.
+pub fn verify_vc_schema(vc: &Credential) -> Vec<VerificationError> {
+    let value = match serde_json::to_value(vc) {
+        Ok(value) => value,
+        Err(e) => return vec![VerificationError::Serialize(e.to_string())],
+    };
+
+    let mut errors = validate(include_str!("../../docs/templates/vc_schema.json"), &value);
+    let schema = select_schema(&vc.credential_subject.types);
+    errors.extend(validate(schema.schema, &value["credentialSubject"]));
+    errors
+}
+
+pub fn verify_vc_info(vc: &Credential) -> Vec<VerificationError> {
+    let mut errors = vec![];
+
     let context = &vc.context;
-    let verified_context =
-        context.len() == 2 && context[0] == CONTEXT[0] && context[1] == CONTEXT[1];
+    if context.len() != 2 || context[0] != CONTEXT[0] || context[1] != CONTEXT[1] {
+        errors.push(VerificationError::InvalidContext(context.clone()));
+    }
+
+    if vc.types.first() != Some(&CredentialType::VerifiableCredential) {
+        errors.push(VerificationError::InvalidType);
+    }
+
+    errors
+}
+
+/// The credential must be issued to `subject`, with one value for each assertion.
+pub fn verify_vc_subject(vc: &Credential, subject: &AccountId) -> Vec<SubjectFailure> {
+    let mut failures = vec![];
+
+    let credential_subject = &vc.credential_subject;
+    let expected = hex::encode(subject);
+    if !hex_eq(&credential_subject.id, &expected) {
+        failures.push(SubjectFailure::IdMismatch {
+            expected,
+            found: credential_subject.id.clone(),
+        });
+    }
 
-    let types = &vc.types;
-    let verified_types = types[0] == CredentialType::VerifiableCredential;
+    if credential_subject.values.len() != credential_subject.assertions.len() {
+        failures.push(SubjectFailure::ValuesMismatch {
+            assertions: credential_subject.assertions.len(),
+            values: credential_subject.values.len(),
+        });
+    }
 
-    verified_context && verified_types
+    failures
 }
 
-/// TODO:
-/// This data structure is the main content of VC, including the core content of assertion.
-/// How to verify this part accurately and effectively?
-pub fn verify_vc_subject(_vc: &Credential) -> bool {
-    true
+/// The credential must be issued by one of the registered `enclaves`, identified by its vc_pubkey.
+pub fn verify_vc_issuer(
+    vc: &Credential,
+    enclaves: &[Enclave<AccountId, String>],
+) -> Vec<IssuerFailure> {
+    let issuer = &vc.issuer;
+    let enclave = enclaves
+        .iter()
+        .find(|enclave| mrenclave_to_bs58(&enclave.mr_enclave) == issuer.mrenclave);
+    let enclave = match enclave {
+        Some(enclave) => enclave,
+        None => return vec![IssuerFailure::UnknownMrenclave(issuer.mrenclave.clone())],
+    };
+
+    match &enclave.vc_pubkey {
+        Some(vc_pubkey) => {
+            let expected = hex::encode(vc_pubkey);
+            if hex_eq(&issuer.id, &expected) {
+                vec![]
+            } else {
+                vec![IssuerFailure::IdMismatch {
+                    expected,
+                    found: issuer.id.clone(),
+                }]
+            }
+        }
+        None => vec![IssuerFailure::MissingVcPubkey],
+    }
 }
 
-/// TODO:
-/// Here is the RA related verification
-pub fn verify_vc_issuer(_vc: &Credential) -> bool {
-    true
+/// Verify the proof of a credential as received from the worker. The proof signs the JSON the
+/// worker serialized, which a `Credential` round trip doesn't reproduce: members it doesn't know
+/// or skips would be lost.
+pub fn verify_vc_json_proof(
+    vc_pubkey: &ed25519::Public,
+    vc_json: &str,
+) -> Result<(), VerificationError> {
+    let value: Value =
+        serde_json::from_str(vc_json).map_err(|e| VerificationError::Serialize(e.to_string()))?;
+    verify_vc_value_proof(vc_pubkey, &value)
 }
 
-pub fn verify_vc_proof(vc_pubkey: &ed25519::Public, vc: &Credential) -> Result<bool, String> {
-    let mut value = serde_json::to_value(vc).map_err(|e| format!("{:?}", e))?;
-    let proof = vc.proof.clone().expect("Proof");
-    let sig = proof.proof_value;
-    let sig = hex::decode(sig).map_err(|e| format!("{:?}", e))?;
+/// Same as `verify_vc_json_proof`, for a credential parsed into a JSON `value` in document order.
+pub fn verify_vc_value_proof(
+    vc_pubkey: &ed25519::Public,
+    value: &Value,
+) -> Result<(), VerificationError> {
+    let proof_value = value["proof"]["proofValue"]
+        .as_str()
+        .ok_or(VerificationError::MissingProof)?;
+    let message = signing_input(value, Canonicalization::DocumentOrder)
+        .map_err(|e| VerificationError::Serialize(e.to_string()))?;
 
-    value["proof"] =
-        serde_json::to_value::<Option<String>>(None).map_err(|e| format!("{:?}", e))?;
+    verify_signature(vc_pubkey, proof_value, &message)
+}
 
-    let vc: Credential = serde_json::from_value(value).map_err(|e| format!("{:?}", e))?;
-    let message = serde_json::to_string(&vc).map_err(|e| format!("{:?}", e))?;
-    let signature =
-        ed25519::Signature::from_slice(&sig).ok_or_else(|| "signature error.".to_string())?;
+fn verify_signature(
+    vc_pubkey: &ed25519::Public,
+    proof_value: &str,
+    message: &[u8],
+) -> Result<(), VerificationError> {
+    let sig = hex::decode(proof_value)
+        .map_err(|e| VerificationError::InvalidProofValue(e.to_string()))?;
+    let signature = ed25519::Signature::from_slice(&sig)
+        .ok_or_else(|| VerificationError::InvalidProofValue(format!("{} bytes", sig.len())))?;
 
-    Ok(Ed25519Pair::verify(&signature, message, vc_pubkey))
+    if Ed25519Pair::verify(&signature, message, vc_pubkey) {
+        Ok(())
+    } else {
+        Err(VerificationError::BadSignature)
+    }
+}
+
+/// Check `vc` against its `VCRegistry` entry `context`, `None` when there is none.
+/// A revoked credential is removed from the registry. The registered hash is the blake2_256 of
+/// `vc_json` as the worker returned it. The registered assertion is checked with
+/// `verify_vc_assertion`, the outcome is `Inconclusive` when it can't be.
+pub fn verify_vc_registry(
+    vc_json: &str,
+    vc: &Credential,
+    context: Option<&VCContext>,
+) -> StageOutcome {
+    let context = match context {
+        Some(context) => context,
+        None => return StageOutcome::Failed(vec![VerificationError::NotRegistered]),
+    };
+
+    let mut errors = vec![];
+    if context.status == Status::Disabled {
+        errors.push(VerificationError::Disabled);
+    }
+
+    let hash = H256::from(blake2_256(vc_json.as_bytes()));
+    if hash != context.hash {
+        errors.push(VerificationError::HashMismatch {
+            expected: context.hash,
+            found: hash,
+        });
+    }
+
+    let registered_subject = hex::encode(&context.subject);
+    if !hex_eq(&registered_subject, &vc.credential_subject.id) {
+        errors.push(VerificationError::RegisteredSubjectMismatch {
+            expected: registered_subject,
+            found: vc.credential_subject.id.clone(),
+        });
+    }
+
+    let (assertion_errors, unchecked) = verify_vc_assertion(vc, &context.assertion);
+    errors.extend(assertion_errors);
+
+    StageOutcome::from_checks(errors, unchecked)
+}
+
+/// Check that `vc` is a credential of `assertion`, its parameters included.
+///
+/// The kind is checked with the `expected_subject_type` of the assertion registry. When the
+/// assertion has none, the credential must at least not be of another known assertion. The
+/// parameters are checked where the credential logic is known to hold them: as `$discord_*_id`,
+/// `$minimum_amount` and `$network` items, see `docs/vc-examples`. What can't be checked is
+/// returned as `Unchecked`.
+pub fn verify_vc_assertion(
+    vc: &Credential,
+    assertion: &Assertion,
+) -> (Vec<VerificationError>, Vec<Unchecked>) {
+    let found = &vc.credential_subject.types;
+    let mismatch = || {
+        let error = VerificationError::AssertionMismatch {
+            assertion: format!("{:?}", assertion),
+            found: found.clone(),
+        };
+        (vec![error], vec![])
+    };
+
+    match assertion.info().expected_subject_type {
+        Some(expected) if expected != found => return mismatch(),
+        Some(_) => {}
+        None if is_known_subject_type(found) => return mismatch(),
+        None => {
+            let unchecked = Unchecked::AssertionKind {
+                assertion: assertion.info().id.to_string(),
+                found: found.clone(),
+            };
+            return (vec![], vec![unchecked]);
+        }
+    }
+
+    let logic = &vc.credential_subject.assertions;
+    let mut errors = vec![];
+    let mut check = |param: &'static str, expected: Vec<String>, eq: fn(&str, &str) -> bool| {
+        let found = logic_values(logic, param);
+        let matches = expected.len() == found.len()
+            && expected
+                .iter()
+                .all(|expected| found.iter().any(|found| eq(expected, found)));
+        if !matches {
+            errors.push(VerificationError::AssertionParamMismatch {
+                param,
+                expected,
+                found: found.into_iter().map(str::to_string).collect(),
+            });
+        }
+    };
+    fn text(value: &ParameterString) -> Vec<String> {
+        vec![String::from_utf8_lossy(value).into_owned()]
+    }
+    fn text_eq(expected: &str, found: &str) -> bool {
+        expected == found
+    }
+    fn amount_eq(expected: &str, found: &str) -> bool {
+        compare_decimal(expected, found)
+            .map_or(expected == found, |ordering| ordering == Ordering::Equal)
+    }
+
+    match assertion {
+        Assertion::A1 | Assertion::A6 => {}
+        Assertion::A2(guild_id) => check("$discord_guild_id", text(guild_id), text_eq),
+        Assertion::A3(guild_id, channel_id, role_id) => {
+            check("$discord_guild_id", text(guild_id), text_eq);
+            check("$discord_channel_id", text(channel_id), text_eq);
+            check("$discord_role_id", text(role_id), text_eq);
+        }
+        Assertion::A4(minimum_amount)
+        | Assertion::A7(minimum_amount)
+        | Assertion::A10(minimum_amount)
+        | Assertion::A11(minimum_amount) => {
+            check("$minimum_amount", text(minimum_amount), amount_eq)
+        }
+        // the worker writes the networks by their variant names, e.g. `Litentry`
+        Assertion::A8(networks) => check(
+            "$network",
+            networks
+                .iter()
+                .map(|network| format!("{:?}", network))
+                .collect(),
+            text_eq,
+        ),
+        Assertion::A9 | Assertion::A13(..) | Assertion::A14 | Assertion::Achainable(..) => {
+            let unchecked = Unchecked::AssertionParams(assertion.info().id.to_string());
+            return (vec![], vec![unchecked]);
+        }
+    }
+
+    (errors, vec![])
+}
+
+/// The subject type is pinned to an assertion, in the registry or by a schema.
+fn is_known_subject_type(subject_type: &str) -> bool {
+    ASSERTION_INFOS
+        .iter()
+        .any(|info| info.expected_subject_type == Some(subject_type))
+        || schema_for_subject_type(subject_type).is_some()
+}
+
+/// The `dst` of the `src == dst` items of `logic` whose `src` is `variable`, at any depth.
+fn logic_values<'a>(logic: &'a [AssertionLogic], variable: &str) -> Vec<&'a str> {
+    let mut values = vec![];
+    for item in logic {
+        match item {
+            AssertionLogic::Item { src, op, dst } => {
+                if src == variable && *op == Op::Equal {
+                    values.push(dst.as_str());
+                }
+            }
+            AssertionLogic::And { items } | AssertionLogic::Or { items } => {
+                for item in items {
+                    values.extend(logic_values(std::slice::from_ref(item.as_ref()), variable));
+                }
+            }
+        }
+    }
+    values
+}
+
+/// Compare hex strings regardless of case and `0x` prefix.
+pub(crate) fn hex_eq(left: &str, right: &str) -> bool {
+    let left = left.trim_start_matches("0x");
+    let right = right.trim_start_matches("0x");
+    left.eq_ignore_ascii_case(right)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::primitives::{address::Address32, MrEnclave, VCIndex};
+    use crate::vc_management::{
+        fixtures::{credential, enclave, sample, signed, A1},
+        validity::FixedClock,
+    };
+    use std::time::Duration;
+
+    const ALICE: &str = "d43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d";
+
+    /// A parachain with a single enclave and VCRegistry entry.
+    struct MockParachain {
+        vc_pubkey: ed25519::Public,
+        enclave: Enclave<AccountId, String>,
+        context: Option<VCContext>,
+    }
+
+    impl ParachainPatch for MockParachain {
+        fn delegatee(&self, _account: Address32) -> ApiResult<Option<()>> {
+            Ok(None)
+        }
+
+        fn enclave_count(&self) -> ApiResult<Option<u64>> {
+            Ok(Some(1))
+        }
+
+        fn enclave(&self, _enclave_count: u64) -> ApiResult<Option<Enclave<AccountId, String>>> {
+            Ok(Some(self.enclave.clone()))
+        }
+
+        fn enclaves(&self) -> ApiResult<Vec<Enclave<AccountId, String>>> {
+            Ok(vec![self.enclave.clone()])
+        }
+
+        fn get_shard(&self) -> ApiResult<MrEnclave> {
+            Ok(self.enclave.mr_enclave)
+        }
+
+        fn get_tee_shielding_pubkey(&self) -> ApiResult<rsa::RsaPublicKey> {
+            Err(ApiClientError::Other(
+                "the mock parachain has no shielding key".into(),
+            ))
+        }
+
+        fn get_vc_pubkey(&self) -> ApiResult<ed25519::Public> {
+            Ok(self.vc_pubkey)
+        }
+
+        fn get_vc_context(&self, _vc_index: &VCIndex) -> ApiResult<Option<VCContext>> {
+            Ok(self.context.clone())
+        }
+    }
+
+    fn account(hex_pubkey: &str) -> AccountId {
+        let mut pubkey = [0u8; 32];
+        pubkey.copy_from_slice(&hex::decode(hex_pubkey).unwrap());
+        AccountId::new(pubkey)
+    }
+
+    #[test]
+    fn verify_vc_subject_works() {
+        let mut vc = credential();
+        assert!(verify_vc_subject(&vc, &account(ALICE)).is_empty());
+
+        let bob = AccountId::new([2u8; 32]);
+        vc.credential_subject.values.push(true);
+        assert_eq!(
+            verify_vc_subject(&vc, &bob),
+            vec![
+                SubjectFailure::IdMismatch {
+                    expected: hex::encode(&bob),
+                    found: ALICE.to_string(),
+                },
+                SubjectFailure::ValuesMismatch {
+                    assertions: 1,
+                    values: 2,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn verify_vc_issuer_works() {
+        let vc = credential();
+        let mut enclave = enclave(&vc);
+        assert!(verify_vc_issuer(&vc, &[enclave.clone()]).is_empty());
+
+        enclave.vc_pubkey = Some(vec![1u8; 32]);
+        assert_eq!(
+            verify_vc_issuer(&vc, &[enclave.clone()]),
+            vec![IssuerFailure::IdMismatch {
+                expected: hex::encode([1u8; 32]),
+                found: vc.issuer.id.clone(),
+            }]
+        );
+
+        enclave.mr_enclave = [0u8; 32];
+        assert_eq!(
+            verify_vc_issuer(&vc, &[enclave]),
+            vec![IssuerFailure::UnknownMrenclave(vc.issuer.mrenclave.clone())]
+        );
+    }
+
+    #[test]
+    fn verify_vc_reports_each_stage() {
+        let mut vc = credential();
+        vc.proof = None;
+        vc.expiration_timestamp = Some(100);
+        let vc_json = serde_json::to_string(&vc).unwrap();
+        let vc_pubkey = ed25519::Public::from_raw([0u8; 32]);
+        let enclaves = [enclave(&vc)];
+        let validity = ValidityWindow::new()
+            .with_clock(FixedClock(200))
+            .with_skew(Duration::ZERO);
+
+        let report =
+            verify_vc(&vc_pubkey, &vc_json, &account(ALICE), &enclaves, &validity).unwrap();
+        assert!(!report.is_valid());
+        assert_eq!(report.stages.len(), 7);
+        assert_eq!(
+            report.outcome(VerificationStage::Subject),
+            Some(&StageOutcome::Passed)
+        );
+        assert_eq!(
+            report.outcome(VerificationStage::ProofSignature),
+            Some(&StageOutcome::Failed(vec![VerificationError::MissingProof]))
+        );
+        assert_eq!(
+            report.outcome(VerificationStage::Validity),
+            Some(&StageOutcome::Failed(vec![VerificationError::Expired {
+                expiration_timestamp: 100,
+                now: 200,
+            }]))
+        );
+        assert_eq!(
+            report.outcome(VerificationStage::OnChainStatus),
+            Some(&StageOutcome::Skipped)
+        );
+    }
+
+    #[test]
+    fn verify_vc_json_proof_works() {
+        let vc_json = A1;
+        let vc = credential();
+        let vc_pubkey: [u8; 32] = hex::decode(&vc.issuer.id).unwrap().try_into().unwrap();
+        let vc_pubkey = ed25519::Public::from_raw(vc_pubkey);
+
+        assert_eq!(verify_vc_json_proof(&vc_pubkey, vc_json), Ok(()));
+        assert_eq!(
+            verify_vc_json_proof(&vc_pubkey, &vc_json.replacen("true", "false", 1)),
+            Err(VerificationError::BadSignature)
+        );
+    }
+
+    #[test]
+    fn verify_vc_checks_the_proof_of_the_worker_json() {
+        let pair = Ed25519Pair::from_seed(&[1u8; 32]);
+        let mut value = serde_json::to_value(credential()).unwrap();
+        // Members a `Credential` round trip would drop are still signed
+        value["credentialSubject"]["unknown"] = Value::Bool(true);
+        let vc_json = signed(value, &pair);
+        let vc: Credential = serde_json::from_str(&vc_json).unwrap();
+        let validity = ValidityWindow::new()
+            .with_clock(FixedClock(vc.issuance_timestamp))
+            .with_skew(Duration::ZERO);
+
+        let report = verify_vc(
+            &pair.public(),
+            &vc_json,
+            &account(ALICE),
+            &[enclave(&vc)],
+            &validity,
+        )
+        .unwrap();
+        assert_eq!(
+            report.outcome(VerificationStage::ProofSignature),
+            Some(&StageOutcome::Passed)
+        );
+
+        let round_trip = serde_json::to_string(&vc).unwrap();
+        assert_eq!(
+            verify_vc_json_proof(&pair.public(), &round_trip),
+            Err(VerificationError::BadSignature)
+        );
+    }
+
+    #[test]
+    fn verify_vc_online_checks_the_proof_of_the_worker_json() {
+        let pair = Ed25519Pair::from_seed(&[1u8; 32]);
+        let mut value = serde_json::to_value(credential()).unwrap();
+        value["credentialSubject"]["unknown"] = Value::Bool(true);
+        let vc_json = signed(value, &pair);
+        let vc: Credential = serde_json::from_str(&vc_json).unwrap();
+        let hash = H256::from(blake2_256(vc_json.as_bytes()));
+        let parachain = MockParachain {
+            vc_pubkey: pair.public(),
+            enclave: enclave(&vc),
+            context: Some(VCContext::new(account(ALICE), Assertion::A1, hash)),
+        };
+
+        let report =
+            verify_vc_online(&parachain, &vc_json, &account(ALICE), &Default::default()).unwrap();
+        assert_eq!(
+            report.outcome(VerificationStage::ProofSignature),
+            Some(&StageOutcome::Passed)
+        );
+        assert_eq!(
+            report.outcome(VerificationStage::OnChainStatus),
+            Some(&StageOutcome::Passed)
+        );
+    }
+
+    #[test]
+    fn verify_vc_registry_works() {
+        let vc_json = A1;
+        let vc = credential();
+        let hash = H256::from(blake2_256(vc_json.as_bytes()));
+        let mut context = VCContext::new(account(ALICE), Assertion::A1, hash);
+        assert_eq!(
+            verify_vc_registry(vc_json, &vc, Some(&context)),
+            StageOutcome::Passed
+        );
+
+        assert_eq!(
+            verify_vc_registry(vc_json, &vc, None),
+            StageOutcome::Failed(vec![VerificationError::NotRegistered])
+        );
+
+        context.status = Status::Disabled;
+        context.assertion = Assertion::A6;
+        context.hash = H256::zero();
+        assert_eq!(
+            verify_vc_registry(vc_json, &vc, Some(&context)),
+            StageOutcome::Failed(vec![
+                VerificationError::Disabled,
+                VerificationError::HashMismatch {
+                    expected: H256::zero(),
+                    found: hash,
+                },
+                VerificationError::AssertionMismatch {
+                    assertion: "A6".to_string(),
+                    found: "Basic Identity Verification".to_string(),
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn verify_vc_registry_checks_unpinned_assertions() {
+        let vc_json = A1;
+        let mut vc = credential();
+        let hash = H256::from(blake2_256(vc_json.as_bytes()));
+        let mut context = VCContext::new(account(ALICE), Assertion::A13(account(ALICE)), hash);
+
+        // An A1 credential is not one of A13, even if A13 credentials are not pinned
+        assert_eq!(
+            verify_vc_registry(vc_json, &vc, Some(&context)),
+            StageOutcome::Failed(vec![VerificationError::AssertionMismatch {
+                assertion: format!("{:?}", Assertion::A13(account(ALICE))),
+                found: "Basic Identity Verification".to_string(),
+            }])
+        );
+
+        vc.credential_subject.types = "Polkadot Decoded 2023".to_string();
+        context.assertion = Assertion::A14;
+        assert_eq!(
+            verify_vc_registry(vc_json, &vc, Some(&context)),
+            StageOutcome::Inconclusive(vec![Unchecked::AssertionKind {
+                assertion: "A14".to_string(),
+                found: "Polkadot Decoded 2023".to_string(),
+            }])
+        );
+    }
+
+    #[test]
+    fn verify_vc_registry_checks_the_parameters() {
+        let vc_json = include_str!("../../docs/vc-examples/a4.json");
+        let vc = sample(vc_json);
+        let hash = H256::from(blake2_256(vc_json.as_bytes()));
+        let amount = |amount: &str| Assertion::A4(amount.as_bytes().to_vec().try_into().unwrap());
+        let mut context = VCContext::new(account(ALICE), amount("1.001"), hash);
+        assert_eq!(
+            verify_vc_registry(vc_json, &vc, Some(&context)),
+            StageOutcome::Passed
+        );
+
+        context.assertion = amount("1.0010");
+        assert_eq!(
+            verify_vc_registry(vc_json, &vc, Some(&context)),
+            StageOutcome::Passed
+        );
+
+        context.assertion = amount("1000");
+        assert_eq!(
+            verify_vc_registry(vc_json, &vc, Some(&context)),
+            StageOutcome::Failed(vec![VerificationError::AssertionParamMismatch {
+                param: "$minimum_amount",
+                expected: vec!["1000".to_string()],
+                found: vec!["1.001".to_string()],
+            }])
+        );
+    }
 }
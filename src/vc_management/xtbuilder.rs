@@ -1,21 +1,28 @@
 use crate::{
-    primitives::{assertion::Assertion, MrEnclave},
+    primitives::{assertion::Assertion, params::AssertionError, MrEnclave},
     vc_management::VC_PALLET_NAME,
     ApiClient,
 };
 use sp_core::H256;
 use substrate_api_client::{
-    ac_compose_macros::compose_extrinsic,
+    ac_node_api::Metadata,
     ac_primitives::{
         extrinsics::{CallIndex, UncheckedExtrinsicV4},
         Config, ExtrinsicParams, SignExtrinsic,
     },
+    api::{Error as ApiClientError, Result as ApiResult},
 };
 
 pub type VCRequestFn = (CallIndex, H256, Assertion);
 pub type VCDisableFn = (CallIndex, H256);
 pub type VCRevokeFn = (CallIndex, H256);
 
+impl From<AssertionError> for ApiClientError {
+    fn from(error: AssertionError) -> Self {
+        ApiClientError::Other(Box::new(error))
+    }
+}
+
 pub trait VcManagementXtBuilder {
     type Extrinsic<Call>;
 
@@ -23,11 +30,14 @@ pub trait VcManagementXtBuilder {
         &self,
         shard: &MrEnclave,
         assertion: &Assertion,
-    ) -> Self::Extrinsic<VCRequestFn>;
+    ) -> ApiResult<Self::Extrinsic<VCRequestFn>>;
 
-    fn build_extrinsic_disable_vc(&self, vc_index: &H256) -> Self::Extrinsic<VCDisableFn>;
+    fn build_extrinsic_disable_vc(
+        &self,
+        vc_index: &H256,
+    ) -> ApiResult<Self::Extrinsic<VCDisableFn>>;
 
-    fn build_extrinsic_revoke_vc(&self, vc_index: &H256) -> Self::Extrinsic<VCRevokeFn>;
+    fn build_extrinsic_revoke_vc(&self, vc_index: &H256) -> ApiResult<Self::Extrinsic<VCRevokeFn>>;
 }
 
 impl<T> VcManagementXtBuilder for ApiClient<T>
@@ -45,30 +55,125 @@ where
         &self,
         shard: &MrEnclave,
         assertion: &Assertion,
-    ) -> Self::Extrinsic<VCRequestFn> {
-        // compose_extrinsic!(
-        //     &self.api,
-        //     VC_PALLET_NAME,
-        //     "request_vc",
-        //     H256::from(shard),
-        //     assertion.clone()
-        // )
-
-        todo!()
+    ) -> ApiResult<Self::Extrinsic<VCRequestFn>> {
+        // a malformed assertion would only fail in the worker, after the fee is paid
+        assertion.validate()?;
+        let call = compose_call_request_vc(self.api.metadata(), shard, assertion)?;
+        self.compose_extrinsic(call)
+    }
+
+    fn build_extrinsic_disable_vc(
+        &self,
+        vc_index: &H256,
+    ) -> ApiResult<Self::Extrinsic<VCDisableFn>> {
+        let call = compose_call_disable_vc(self.api.metadata(), vc_index)?;
+        self.compose_extrinsic(call)
+    }
+
+    fn build_extrinsic_revoke_vc(&self, vc_index: &H256) -> ApiResult<Self::Extrinsic<VCRevokeFn>> {
+        let call = compose_call_revoke_vc(self.api.metadata(), vc_index)?;
+        self.compose_extrinsic(call)
+    }
+}
+
+/// VCManagement calls, composed from the call indexes in the runtime `metadata`. They fail when
+/// the runtime has no such pallet or call.
+pub fn compose_call_request_vc(
+    metadata: &Metadata,
+    shard: &MrEnclave,
+    assertion: &Assertion,
+) -> ApiResult<VCRequestFn> {
+    Ok((
+        call_index(metadata, "request_vc")?,
+        H256::from(shard),
+        assertion.clone(),
+    ))
+}
+
+pub fn compose_call_disable_vc(metadata: &Metadata, vc_index: &H256) -> ApiResult<VCDisableFn> {
+    Ok((call_index(metadata, "disable_vc")?, *vc_index))
+}
+
+pub fn compose_call_revoke_vc(metadata: &Metadata, vc_index: &H256) -> ApiResult<VCRevokeFn> {
+    Ok((call_index(metadata, "revoke_vc")?, *vc_index))
+}
+
+/// The index of the VCManagement `call` in `metadata`, what `compose_call!` would panic on.
+fn call_index(metadata: &Metadata, call: &str) -> ApiResult<CallIndex> {
+    let pallet = metadata.pallet_by_name(VC_PALLET_NAME).ok_or_else(|| {
+        ApiClientError::Other(format!("[+] pallet {} not found in metadata", VC_PALLET_NAME).into())
+    })?;
+    let call_index = pallet.call_indexes.get(call).ok_or_else(|| {
+        ApiClientError::Other(
+            format!("[+] call {}.{} not found in metadata", VC_PALLET_NAME, call).into(),
+        )
+    })?;
+
+    Ok([pallet.index, *call_index])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        primitives::assertion::ParameterString,
+        utils::mock_metadata::{mock_metadata, VCManagementCall, VC_PALLET_INDEX},
+    };
+    use codec::Encode;
+
+    #[test]
+    fn compose_call_request_vc_works() {
+        let metadata = mock_metadata();
+        let shard = [7u8; 32];
+        let amount = ParameterString::try_from(b"10".to_vec()).unwrap();
+        let assertion = Assertion::A4(amount);
+
+        let call = compose_call_request_vc(&metadata, &shard, &assertion).unwrap();
+
+        let mut expected = vec![VC_PALLET_INDEX, 0];
+        expected.extend_from_slice(&shard);
+        // A4 variant index, compact length, "10"
+        expected.extend_from_slice(&[3, 8, b'1', b'0']);
+        assert_eq!(call.encode(), expected);
+
+        let runtime_call = VCManagementCall::request_vc {
+            shard: H256::from(shard),
+            assertion,
+        };
+        assert_eq!(call.encode()[1..], runtime_call.encode()[..]);
     }
 
-    fn build_extrinsic_disable_vc(&self, vc_index: &H256) -> Self::Extrinsic<VCDisableFn> {
-        // compose_extrinsic!(self.api, VC_PALLET_NAME, "disable_vc", *vc_index)
+    #[test]
+    fn compose_call_disable_vc_works() {
+        let metadata = mock_metadata();
+        let vc_index = H256::repeat_byte(1);
 
-        todo!()
+        let call = compose_call_disable_vc(&metadata, &vc_index).unwrap();
+
+        let mut expected = vec![VC_PALLET_INDEX, 1];
+        expected.extend_from_slice(vc_index.as_bytes());
+        assert_eq!(call.encode(), expected);
     }
 
-    fn build_extrinsic_revoke_vc(&self, vc_index: &H256) -> Self::Extrinsic<VCRevokeFn> {
-        // let xt: UncheckedExtrinsicV4<_, _, _, _> =
-        //     compose_extrinsic!(self.api, VC_PALLET_NAME, "revoke_vc", *vc_index);
+    #[test]
+    fn compose_call_revoke_vc_works() {
+        let metadata = mock_metadata();
+        let vc_index = H256::repeat_byte(2);
+
+        let call = compose_call_revoke_vc(&metadata, &vc_index).unwrap();
 
-        // xt
+        let mut expected = vec![VC_PALLET_INDEX, 2];
+        expected.extend_from_slice(vc_index.as_bytes());
+        assert_eq!(call.encode(), expected);
+    }
 
-        todo!()
+    #[test]
+    fn unknown_calls_are_errors() {
+        let metadata = mock_metadata();
+        assert_eq!(
+            call_index(&metadata, "request_vc").unwrap(),
+            [VC_PALLET_INDEX, 0]
+        );
+        assert!(call_index(&metadata, "burn_vc").is_err());
     }
 }